@@ -10,7 +10,10 @@ interface ApiService {
     suspend fun getMe(): UserResponse
 
     @POST("expenses")
-    suspend fun createExpense(@Body request: CreateExpenseRequest): ExpenseResponse
+    suspend fun createExpense(
+        @Body request: CreateExpenseRequest,
+        @Header("Idempotency-Key") idempotencyKey: String,
+    ): ExpenseResponse
 
     @GET("expenses")
     suspend fun getExpenses(
@@ -18,11 +21,15 @@ interface ApiService {
         @Query("end_date") endDate: String? = null,
         @Query("category") category: String? = null,
         @Query("per_page") perPage: Int = 50,
-    ): List<ExpenseResponse>
+        @Query("cursor") cursor: String? = null,
+    ): PageResponse<ExpenseResponse>
 
     @DELETE("expenses/{id}")
     suspend fun deleteExpense(@Path("id") id: String): Response<Unit>
 
+    @POST("sync")
+    suspend fun sync(@Body request: SyncRequest): SyncResponse
+
     @GET("analytics/daily")
     suspend fun getDailySummary(@Query("date") date: String? = null): ExpenseSummaryResponse
 
@@ -48,10 +55,16 @@ interface ApiService {
     suspend fun registerDevice(@Body request: Map<String, String>)
 
     @POST("meeting-notes")
-    suspend fun createMeetingNote(@Body request: CreateMeetingNoteRequest): MeetingNoteResponse
+    suspend fun createMeetingNote(
+        @Body request: CreateMeetingNoteRequest,
+        @Header("Idempotency-Key") idempotencyKey: String,
+    ): MeetingNoteResponse
 
     @GET("meeting-notes")
-    suspend fun getMeetingNotes(): List<MeetingNoteResponse>
+    suspend fun getMeetingNotes(
+        @Query("per_page") perPage: Int = 100,
+        @Query("cursor") cursor: String? = null,
+    ): PageResponse<MeetingNoteResponse>
 
     @GET("meeting-notes/{id}")
     suspend fun getMeetingNote(@Path("id") id: String): MeetingNoteResponse
@@ -3,11 +3,12 @@ package com.lilyai.app.data.remote.dto
 import com.google.gson.annotations.SerializedName
 
 data class EffectiveBudgetResponse(
-    @SerializedName("daily_budget") val dailyBudget: String,
-    @SerializedName("effective_budget_today") val effectiveBudgetToday: String,
+    val period: String,
+    @SerializedName("budget") val dailyBudget: String,
+    @SerializedName("effective_budget") val effectiveBudgetToday: String,
     @SerializedName("carried_over") val carriedOver: String,
-    @SerializedName("spent_today") val spentToday: String,
-    @SerializedName("remaining_today") val remainingToday: String
+    @SerializedName("spent") val spentToday: String,
+    @SerializedName("remaining") val remainingToday: String
 )
 
 data class SetBudgetRequest(
@@ -17,7 +18,9 @@ data class SetBudgetRequest(
 data class BudgetSettingResponse(
     val id: String,
     @SerializedName("user_id") val userId: String,
-    @SerializedName("daily_budget") val dailyBudget: String,
+    @SerializedName("amount") val dailyBudget: String,
+    val period: String,
+    @SerializedName("carry_over") val carryOver: String,
     @SerializedName("created_at") val createdAt: String,
     @SerializedName("updated_at") val updatedAt: String
 )
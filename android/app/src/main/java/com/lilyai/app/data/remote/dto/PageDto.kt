@@ -0,0 +1,9 @@
+package com.lilyai.app.data.remote.dto
+
+import com.google.gson.annotations.SerializedName
+
+data class PageResponse<T>(
+    val items: List<T>,
+    @SerializedName("next_cursor") val nextCursor: String?,
+    val total: Long,
+)
@@ -0,0 +1,53 @@
+package com.lilyai.app.data.remote.dto
+
+import com.lilyai.app.data.local.ExpenseEntity
+import com.google.gson.annotations.SerializedName
+
+data class SyncRequest(
+    @SerializedName("sync_token") val syncToken: String?,
+    val expenses: List<ExpenseMutation> = emptyList(),
+)
+
+data class ExpenseMutation(
+    val id: String,
+    @SerializedName("updated_at") val updatedAt: String,
+    val deleted: Boolean = false,
+    val amount: Double? = null,
+    val category: String? = null,
+    val note: String? = null,
+    @SerializedName("expense_date") val expenseDate: String? = null,
+) {
+    companion object {
+        fun from(entity: ExpenseEntity) = ExpenseMutation(
+            id = entity.id, updatedAt = entity.updatedAt,
+            amount = entity.amount, category = entity.category,
+            note = entity.note, expenseDate = entity.expenseDate,
+        )
+    }
+}
+
+data class SyncResponse(
+    val results: List<MutationResult>,
+    val changes: SyncChanges,
+    @SerializedName("sync_token") val syncToken: String,
+    @SerializedName("has_more") val hasMore: Boolean,
+)
+
+data class MutationResult(
+    val entity: String,
+    val id: String,
+    val status: String,
+    val error: String?,
+)
+
+data class SyncChanges(
+    val expenses: List<ExpenseResponse>,
+    @SerializedName("meeting_notes") val meetingNotes: List<MeetingNoteResponse>,
+    val deleted: List<TombstoneResponse>,
+)
+
+data class TombstoneResponse(
+    val entity: String,
+    @SerializedName("entity_id") val entityId: String,
+    @SerializedName("deleted_at") val deletedAt: String,
+)
@@ -5,10 +5,13 @@ import com.lilyai.app.data.local.ExpenseEntity
 import com.lilyai.app.data.remote.ApiService
 import com.lilyai.app.data.remote.dto.CreateExpenseRequest
 import com.lilyai.app.data.remote.dto.EffectiveBudgetResponse
+import com.lilyai.app.data.remote.dto.ExpenseMutation
 import com.lilyai.app.data.remote.dto.SetBudgetRequest
 import com.lilyai.app.data.remote.dto.SubmitDayRequest
+import com.lilyai.app.data.remote.dto.SyncRequest
 import com.lilyai.app.domain.model.*
 import com.lilyai.app.domain.repository.ExpenseRepository
+import com.lilyai.app.ui.screens.login.TokenStore
 import java.util.UUID
 import javax.inject.Inject
 import javax.inject.Singleton
@@ -23,13 +26,15 @@ class ExpenseRepositoryImpl @Inject constructor(
         amount: Double, category: Category, note: String?, date: String
     ): Expense {
         val request = CreateExpenseRequest(amount, category.name.lowercase(), note, date)
+        // Doubles as the idempotency key, so a retried request can't create a
+        // second expense, and as the id if the expense is saved offline
+        val localId = UUID.randomUUID().toString()
         return try {
-            val response = apiService.createExpense(request)
+            val response = apiService.createExpense(request, localId)
             expenseDao.insert(response.toEntity())
             response.toDomain()
         } catch (e: Exception) {
             // Offline: save locally
-            val localId = UUID.randomUUID().toString()
             val now = java.time.Instant.now().toString()
             val entity = ExpenseEntity(
                 id = localId, userId = "", amount = amount,
@@ -46,7 +51,7 @@ class ExpenseRepositoryImpl @Inject constructor(
         startDate: String?, endDate: String?, category: Category?
     ): List<Expense> {
         return try {
-            val response = apiService.getExpenses(startDate, endDate, category?.name?.lowercase())
+            val response = apiService.getExpenses(startDate, endDate, category?.name?.lowercase()).items
             val entities = response.map { it.toEntity() }
             expenseDao.insertAll(entities)
             response.map { it.toDomain() }
@@ -85,19 +90,27 @@ class ExpenseRepositoryImpl @Inject constructor(
     }
 
     override suspend fun syncPendingExpenses() {
-        val unsynced = expenseDao.getUnsyncedExpenses()
-        for (entity in unsynced) {
-            try {
-                val request = CreateExpenseRequest(
-                    entity.amount, entity.category, entity.note, entity.expenseDate
-                )
-                val response = apiService.createExpense(request)
-                expenseDao.delete(entity.id)
-                expenseDao.insert(response.toEntity())
-            } catch (_: Exception) {
-                // Will retry on next sync
+        // Expenses saved offline already have their own UUIDs, so the server
+        // keeps them; retries are safe
+        var mutations = expenseDao.getUnsyncedExpenses().map(ExpenseMutation::from)
+        do {
+            val response = apiService.sync(SyncRequest(TokenStore.getSyncToken(), mutations))
+            for (result in response.results) {
+                when (result.status) {
+                    "applied" -> expenseDao.markSynced(result.id)
+                    // The server's copy wins; it comes back on the next refresh
+                    "conflict" -> expenseDao.delete(result.id)
+                    // Left pending; it will be retried on next sync
+                    else -> {}
+                }
             }
-        }
+            expenseDao.insertAll(response.changes.expenses.map { it.toEntity() })
+            response.changes.deleted
+                .filter { it.entity == "expense" }
+                .forEach { expenseDao.delete(it.entityId) }
+            TokenStore.saveSyncToken(response.syncToken)
+            mutations = emptyList()
+        } while (response.hasMore)
     }
 
     override suspend fun getBudget(): EffectiveBudgetResponse {
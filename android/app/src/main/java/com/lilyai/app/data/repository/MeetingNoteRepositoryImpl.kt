@@ -24,12 +24,13 @@ class MeetingNoteRepositoryImpl @Inject constructor(
         title: String?, durationSecs: Int, localAudioPath: String?
     ): MeetingNote {
         val request = CreateMeetingNoteRequest(title, durationSecs)
+        // Doubles as the idempotency key and as the id if saved offline
+        val localId = java.util.UUID.randomUUID().toString()
         return try {
-            val response = apiService.createMeetingNote(request)
+            val response = apiService.createMeetingNote(request, localId)
             dao.insert(response.toEntity(localAudioPath))
             response.toDomain()
         } catch (e: Exception) {
-            val localId = java.util.UUID.randomUUID().toString()
             val now = java.time.Instant.now().toString()
             val entity = MeetingNoteEntity(
                 id = localId, userId = "", meetingTitle = title ?: "Untitled Meeting",
@@ -45,7 +46,7 @@ class MeetingNoteRepositoryImpl @Inject constructor(
 
     override suspend fun getMeetingNotes(): List<MeetingNote> {
         return try {
-            val response = apiService.getMeetingNotes()
+            val response = apiService.getMeetingNotes().items
             val entities = response.map { it.toEntity(null) }
             dao.insertAll(entities)
             response.map { it.toDomain() }
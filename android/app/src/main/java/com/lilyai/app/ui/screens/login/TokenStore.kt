@@ -8,6 +8,7 @@ object TokenStore {
     private const val KEY_ID_TOKEN = "id_token"
     private const val KEY_ACCESS_TOKEN = "access_token"
     private const val KEY_REFRESH_TOKEN = "refresh_token"
+    private const val KEY_SYNC_TOKEN = "sync_token"
 
     private var prefs: SharedPreferences? = null
 
@@ -29,6 +30,13 @@ object TokenStore {
 
     fun getRefreshToken(): String? = prefs?.getString(KEY_REFRESH_TOKEN, null)
 
+    /** Where the last `POST /sync` left off; cleared with the login. */
+    fun getSyncToken(): String? = prefs?.getString(KEY_SYNC_TOKEN, null)
+
+    fun saveSyncToken(token: String) {
+        prefs?.edit()?.putString(KEY_SYNC_TOKEN, token)?.apply()
+    }
+
     fun clear() {
         prefs?.edit()?.clear()?.apply()
     }
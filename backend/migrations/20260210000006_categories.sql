@@ -0,0 +1,29 @@
+CREATE TABLE categories (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    icon VARCHAR(50),
+    color VARCHAR(7),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(user_id, name)
+);
+
+-- Categories are now validated against the user's own list
+ALTER TABLE expenses DROP CONSTRAINT IF EXISTS expenses_category_check;
+
+-- Seed the default set for existing users
+INSERT INTO categories (user_id, name, icon, color)
+SELECT u.id, d.name, d.icon, d.color
+FROM users u
+CROSS JOIN (VALUES
+    ('food', 'restaurant', '#ef4444'),
+    ('entertainment', 'movie', '#8b5cf6'),
+    ('travel', 'flight', '#3b82f6'),
+    ('bills', 'receipt', '#f59e0b'),
+    ('shopping', 'shopping_bag', '#10b981'),
+    ('other', 'category', '#6b7280')
+) AS d(name, icon, color)
+ON CONFLICT (user_id, name) DO NOTHING;
+
+CREATE INDEX idx_categories_user ON categories(user_id, name);
@@ -0,0 +1,10 @@
+-- Bank transaction ids (OFX FITIDs, or derived ids for QIF) already imported
+-- as expenses, so the same statement cannot be imported twice.
+CREATE TABLE imported_transactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    fitid VARCHAR(255) NOT NULL,
+    expense_id UUID REFERENCES expenses(id) ON DELETE SET NULL,
+    imported_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(user_id, fitid)
+);
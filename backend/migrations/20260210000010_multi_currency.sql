@@ -0,0 +1,34 @@
+-- Existing amounts were all entered in rupees
+ALTER TABLE users ADD COLUMN base_currency VARCHAR(3) NOT NULL DEFAULT 'INR';
+ALTER TABLE expenses ADD COLUMN currency VARCHAR(3) NOT NULL DEFAULT 'INR';
+ALTER TABLE recurring_expenses ADD COLUMN currency VARCHAR(3) NOT NULL DEFAULT 'INR';
+
+-- One unit of from_currency is worth `rate` units of to_currency on rate_date.
+-- Rates are maintained locally; a rate applies until a later one is added.
+CREATE TABLE exchange_rates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    from_currency VARCHAR(3) NOT NULL,
+    to_currency VARCHAR(3) NOT NULL,
+    rate_date DATE NOT NULL,
+    rate DECIMAL(18, 8) NOT NULL CHECK (rate > 0),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(from_currency, to_currency, rate_date),
+    CHECK (from_currency <> to_currency)
+);
+
+-- The rate to convert from one currency to another on a date: the latest
+-- rate on or before that date, in either direction. NULL if there is none.
+CREATE FUNCTION exchange_rate(from_code VARCHAR, to_code VARCHAR, on_date DATE)
+RETURNS DECIMAL AS $$
+    SELECT CASE WHEN from_code = to_code THEN 1 ELSE (
+        SELECT r.rate FROM (
+            SELECT rate, rate_date FROM exchange_rates
+            WHERE from_currency = from_code AND to_currency = to_code AND rate_date <= on_date
+            UNION ALL
+            SELECT 1 / rate, rate_date FROM exchange_rates
+            WHERE from_currency = to_code AND to_currency = from_code AND rate_date <= on_date
+        ) r
+        ORDER BY r.rate_date DESC
+        LIMIT 1
+    ) END
+$$ LANGUAGE SQL STABLE;
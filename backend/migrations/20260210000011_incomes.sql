@@ -0,0 +1,13 @@
+CREATE TABLE incomes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount >= 0),
+    currency VARCHAR(3) NOT NULL DEFAULT 'INR',
+    source VARCHAR(20) NOT NULL CHECK (source IN ('salary', 'freelance', 'business', 'investment', 'rental', 'gift', 'refund', 'other')),
+    note TEXT,
+    income_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_incomes_user_date ON incomes(user_id, income_date);
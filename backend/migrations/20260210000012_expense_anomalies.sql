@@ -0,0 +1,21 @@
+ALTER TABLE users ADD COLUMN anomaly_alerts BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Expenses flagged as unusually large for their category when created.
+-- Amounts and statistics are in the user's base currency at flag time.
+CREATE TABLE expense_anomalies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    expense_id UUID NOT NULL UNIQUE REFERENCES expenses(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category VARCHAR(50) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL,
+    rule VARCHAR(20) NOT NULL CHECK (rule IN ('std_dev', 'percentile')),
+    reason TEXT NOT NULL,
+    mean DECIMAL(12, 2) NOT NULL,
+    std_dev DECIMAL(12, 2) NOT NULL,
+    p95 DECIMAL(12, 2) NOT NULL,
+    sample_size INTEGER NOT NULL,
+    expense_date DATE NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_expense_anomalies_user_date ON expense_anomalies(user_id, expense_date);
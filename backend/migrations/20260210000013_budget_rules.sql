@@ -0,0 +1,12 @@
+-- The budget amount now applies to the chosen period rather than always a day
+ALTER TABLE user_budget_settings RENAME COLUMN daily_budget TO amount;
+
+ALTER TABLE user_budget_settings
+    ADD COLUMN period VARCHAR(10) NOT NULL DEFAULT 'daily'
+        CHECK (period IN ('daily', 'weekly', 'monthly')),
+    ADD COLUMN carry_over VARCHAR(10) NOT NULL DEFAULT 'full'
+        CHECK (carry_over IN ('full', 'none', 'savings', 'overspend', 'capped')),
+    ADD COLUMN carry_over_cap NUMERIC(12,2) CHECK (carry_over_cap >= 0),
+    ADD COLUMN carry_over_periods INTEGER CHECK (carry_over_periods > 0),
+    ADD CONSTRAINT user_budget_settings_cap_check
+        CHECK (carry_over <> 'capped' OR carry_over_cap IS NOT NULL);
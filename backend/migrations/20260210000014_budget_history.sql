@@ -0,0 +1,9 @@
+-- Each row is the budget in force from effective_from until the next row,
+-- so past periods keep the amount and rules that applied at the time.
+ALTER TABLE user_budget_settings ADD COLUMN effective_from DATE;
+UPDATE user_budget_settings SET effective_from = created_at::date;
+ALTER TABLE user_budget_settings ALTER COLUMN effective_from SET NOT NULL;
+
+ALTER TABLE user_budget_settings DROP CONSTRAINT user_budget_settings_user_id_key;
+ALTER TABLE user_budget_settings
+    ADD CONSTRAINT user_budget_settings_user_id_effective_from_key UNIQUE (user_id, effective_from);
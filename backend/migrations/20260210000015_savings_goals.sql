@@ -0,0 +1,32 @@
+CREATE TABLE savings_goals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    target_amount DECIMAL(12, 2) NOT NULL CHECK (target_amount > 0),
+    start_date DATE NOT NULL,
+    deadline DATE CHECK (deadline >= start_date),
+    -- Share of each budget period's unspent amount saved automatically; 0 is off
+    auto_contribute_percent INTEGER NOT NULL DEFAULT 0
+        CHECK (auto_contribute_percent BETWEEN 0 AND 100),
+    achieved_on DATE,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_savings_goals_user ON savings_goals(user_id);
+
+CREATE TABLE goal_contributions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    goal_id UUID NOT NULL REFERENCES savings_goals(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    source VARCHAR(10) NOT NULL CHECK (source IN ('manual', 'auto')),
+    -- Budget period an automatic contribution came from, at most one each
+    period_start DATE,
+    note TEXT,
+    contributed_on DATE NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(goal_id, period_start)
+);
+
+CREATE INDEX idx_goal_contributions_goal ON goal_contributions(goal_id, contributed_on);
@@ -0,0 +1,12 @@
+ALTER TABLE expenses ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_expenses_tags ON expenses USING GIN (tags);
+
+-- array_to_string is only STABLE, but joining text never depends on settings,
+-- so this can be IMMUTABLE and back an expression index.
+CREATE FUNCTION expense_search_vector(note TEXT, tags TEXT[]) RETURNS tsvector
+LANGUAGE sql IMMUTABLE PARALLEL SAFE AS $$
+    SELECT to_tsvector('english', COALESCE(note, '') || ' ' || array_to_string(tags, ' '))
+$$;
+
+CREATE INDEX idx_expenses_search ON expenses USING GIN (expense_search_vector(note, tags));
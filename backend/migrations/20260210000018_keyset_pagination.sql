@@ -0,0 +1,6 @@
+-- Keyset pagination walks these in descending order
+DROP INDEX idx_expenses_user_date;
+CREATE INDEX idx_expenses_user_date ON expenses(user_id, expense_date DESC, created_at DESC, id DESC);
+
+DROP INDEX idx_meeting_notes_user;
+CREATE INDEX idx_meeting_notes_user ON meeting_notes(user_id, created_at DESC, id DESC);
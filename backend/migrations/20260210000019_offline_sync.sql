@@ -0,0 +1,45 @@
+-- Offline sync: every write to a synced row takes a new version from one
+-- sequence, and records the transaction that made it. Clients pull rows by
+-- version; the transaction id lets a pull catch writes that took a version
+-- earlier but committed after the client's last pull.
+CREATE SEQUENCE sync_version_seq;
+
+ALTER TABLE expenses
+    ADD COLUMN row_version BIGINT NOT NULL DEFAULT nextval('sync_version_seq'),
+    ADD COLUMN sync_xid XID8 NOT NULL DEFAULT pg_current_xact_id();
+
+ALTER TABLE meeting_notes
+    ADD COLUMN row_version BIGINT NOT NULL DEFAULT nextval('sync_version_seq'),
+    ADD COLUMN sync_xid XID8 NOT NULL DEFAULT pg_current_xact_id();
+
+CREATE FUNCTION bump_sync_version() RETURNS trigger AS $$
+BEGIN
+    NEW.row_version := nextval('sync_version_seq');
+    NEW.sync_xid := pg_current_xact_id();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER expenses_sync_version
+    BEFORE UPDATE ON expenses
+    FOR EACH ROW EXECUTE FUNCTION bump_sync_version();
+
+CREATE TRIGGER meeting_notes_sync_version
+    BEFORE UPDATE ON meeting_notes
+    FOR EACH ROW EXECUTE FUNCTION bump_sync_version();
+
+CREATE INDEX idx_expenses_sync ON expenses(user_id, row_version);
+CREATE INDEX idx_meeting_notes_sync ON meeting_notes(user_id, row_version);
+
+-- Deleted rows, so offline clients learn to drop their copies
+CREATE TABLE sync_tombstones (
+    entity VARCHAR(20) NOT NULL CHECK (entity IN ('expense', 'meeting_note')),
+    entity_id UUID NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    row_version BIGINT NOT NULL DEFAULT nextval('sync_version_seq'),
+    sync_xid XID8 NOT NULL DEFAULT pg_current_xact_id(),
+    PRIMARY KEY (entity, entity_id)
+);
+
+CREATE INDEX idx_sync_tombstones_user ON sync_tombstones(user_id, row_version);
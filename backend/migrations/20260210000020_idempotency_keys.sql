@@ -0,0 +1,15 @@
+-- Responses to mutating requests sent with an Idempotency-Key header, kept
+-- for a day so retried requests get the first response instead of running
+-- twice. status_code is NULL while the first request is still running.
+CREATE TABLE idempotency_keys (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    key VARCHAR(255) NOT NULL,
+    request_hash CHAR(64) NOT NULL,
+    status_code SMALLINT,
+    content_type TEXT,
+    response_body BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, key)
+);
+
+CREATE INDEX idx_idempotency_keys_created ON idempotency_keys(created_at);
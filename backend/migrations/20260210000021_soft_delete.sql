@@ -0,0 +1,7 @@
+-- Deleted expenses and meeting notes go to the trash first. They can be
+-- restored until the purge job removes them, along with their files.
+ALTER TABLE expenses ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE meeting_notes ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX idx_expenses_trash ON expenses(user_id, deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_meeting_notes_trash ON meeting_notes(user_id, deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,5 @@
+-- Failed reminders are retried with growing gaps up to a limit, and a claim
+-- left pending by a crashed run can be taken over.
+ALTER TABLE daily_reminders
+    ADD COLUMN attempts INT NOT NULL DEFAULT 1,
+    ADD COLUMN claimed_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
@@ -3,10 +3,11 @@ use chrono::NaiveDate;
 use serde::Deserialize;
 use sqlx::PgPool;
 
+use crate::domain::analytics::*;
 use crate::domain::expense::ExpenseSummary;
 use crate::errors::AppError;
 use crate::middleware::auth::AuthenticatedUser;
-use crate::services::analytics_service;
+use crate::services::{analytics_service, forecast_service};
 
 #[derive(Debug, Deserialize)]
 pub struct DateQuery {
@@ -24,7 +25,7 @@ pub async fn daily(
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     axum::extract::Query(query): axum::extract::Query<DateQuery>,
 ) -> Result<Json<ExpenseSummary>, AppError> {
-    let summary = analytics_service::daily_summary(&pool, user.id, query.date).await?;
+    let summary = analytics_service::daily_summary(&pool, &user, query.date).await?;
     Ok(Json(summary))
 }
 
@@ -33,7 +34,7 @@ pub async fn weekly(
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     axum::extract::Query(query): axum::extract::Query<DateQuery>,
 ) -> Result<Json<ExpenseSummary>, AppError> {
-    let summary = analytics_service::weekly_summary(&pool, user.id, query.date).await?;
+    let summary = analytics_service::weekly_summary(&pool, &user, query.date).await?;
     Ok(Json(summary))
 }
 
@@ -42,7 +43,7 @@ pub async fn monthly(
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     axum::extract::Query(query): axum::extract::Query<DateQuery>,
 ) -> Result<Json<ExpenseSummary>, AppError> {
-    let summary = analytics_service::monthly_summary(&pool, user.id, query.date).await?;
+    let summary = analytics_service::monthly_summary(&pool, &user, query.date).await?;
     Ok(Json(summary))
 }
 
@@ -53,10 +54,55 @@ pub async fn category(
 ) -> Result<Json<ExpenseSummary>, AppError> {
     let summary = analytics_service::category_summary(
         &pool,
-        user.id,
+        &user,
         query.start_date,
         query.end_date,
     )
     .await?;
     Ok(Json(summary))
 }
+
+pub async fn cashflow(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    axum::extract::Query(query): axum::extract::Query<CashflowQuery>,
+) -> Result<Json<CashflowReport>, AppError> {
+    let report = analytics_service::cashflow(&pool, &user, &query).await?;
+    Ok(Json(report))
+}
+
+pub async fn timeseries(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    axum::extract::Query(query): axum::extract::Query<TimeseriesQuery>,
+) -> Result<Json<Timeseries>, AppError> {
+    let series = analytics_service::timeseries(&pool, &user, &query).await?;
+    Ok(Json(series))
+}
+
+pub async fn weekly_comparison(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    axum::extract::Query(query): axum::extract::Query<CompareQuery>,
+) -> Result<Json<PeriodComparison>, AppError> {
+    let comparison = analytics_service::weekly_comparison(&pool, &user, &query).await?;
+    Ok(Json(comparison))
+}
+
+pub async fn monthly_comparison(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    axum::extract::Query(query): axum::extract::Query<CompareQuery>,
+) -> Result<Json<PeriodComparison>, AppError> {
+    let comparison = analytics_service::monthly_comparison(&pool, &user, &query).await?;
+    Ok(Json(comparison))
+}
+
+pub async fn forecast(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    axum::extract::Query(query): axum::extract::Query<ForecastQuery>,
+) -> Result<Json<Forecast>, AppError> {
+    let forecast = forecast_service::month_forecast(&pool, &user, query.date).await?;
+    Ok(Json(forecast))
+}
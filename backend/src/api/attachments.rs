@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Multipart, Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::attachment::{AttachmentLink, AttachmentUpload};
+use crate::errors::AppError;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::attachment_service;
+use crate::services::storage_service::StorageService;
+
+pub async fn upload_attachment(
+    State((pool, storage)): State<(PgPool, Option<StorageService>)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(expense_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<AttachmentLink>), AppError> {
+    let storage = storage.ok_or_else(|| AppError::Internal("Storage not configured".into()))?;
+
+    let mut upload: Option<AttachmentUpload> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Validation(format!("Multipart error: {}", e)))?
+    {
+        if field.name() == Some("file") {
+            let file_name = field.file_name().unwrap_or("receipt").to_string();
+            let content_type = field
+                .content_type()
+                .unwrap_or("application/octet-stream")
+                .to_string();
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::Validation(format!("Read error: {}", e)))?
+                .to_vec();
+            upload = Some(AttachmentUpload { file_name, content_type, data });
+        }
+    }
+    let upload = upload.ok_or_else(|| AppError::Validation("No file field in upload".into()))?;
+
+    let attachment =
+        attachment_service::upload_attachment(&pool, &storage, expense_id, user.id, upload).await?;
+    Ok((StatusCode::CREATED, Json(attachment)))
+}
+
+pub async fn list_attachments(
+    State((pool, storage)): State<(PgPool, Option<StorageService>)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(expense_id): Path<Uuid>,
+) -> Result<Json<Vec<AttachmentLink>>, AppError> {
+    let storage = storage.ok_or_else(|| AppError::Internal("Storage not configured".into()))?;
+    let attachments =
+        attachment_service::list_attachments(&pool, &storage, expense_id, user.id).await?;
+    Ok(Json(attachments))
+}
+
+pub async fn delete_attachment(
+    State((pool, storage)): State<(PgPool, Option<StorageService>)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path((expense_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    let storage = storage.ok_or_else(|| AppError::Internal("Storage not configured".into()))?;
+    attachment_service::delete_attachment(&pool, &storage, expense_id, id, user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
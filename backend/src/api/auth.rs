@@ -11,6 +11,9 @@ pub async fn me(
         "email": user.email,
         "name": user.name,
         "notification_time": user.notification_time.to_string(),
+        "timezone": user.timezone,
+        "base_currency": user.base_currency,
+        "anomaly_alerts": user.anomaly_alerts,
         "created_at": user.created_at,
     })))
 }
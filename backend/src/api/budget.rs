@@ -1,13 +1,15 @@
-use axum::{extract::{Query, State}, Extension, Json};
-use chrono::{Duration, NaiveDate};
+use axum::{extract::{Path, Query, State}, http::StatusCode, Extension, Json};
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use sqlx::PgPool;
 
 use crate::domain::budget::*;
+use crate::domain::category::normalize_name;
 use crate::errors::AppError;
 use crate::infrastructure::budget_repo;
 use crate::middleware::auth::AuthenticatedUser;
+use crate::services::{budget_service, category_service};
 
 #[derive(Debug, Deserialize)]
 pub struct BudgetQuery {
@@ -19,53 +21,9 @@ pub async fn get_budget(
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     Query(query): Query<BudgetQuery>,
 ) -> Result<Json<EffectiveBudget>, AppError> {
-    let setting = budget_repo::get(&pool, user.id).await?;
-    let daily_budget = setting.map(|s| s.daily_budget).unwrap_or(Decimal::ZERO);
-
-    if daily_budget <= Decimal::ZERO {
-        return Ok(Json(EffectiveBudget {
-            daily_budget: Decimal::ZERO,
-            effective_budget_today: Decimal::ZERO,
-            carried_over: Decimal::ZERO,
-            spent_today: Decimal::ZERO,
-            remaining_today: Decimal::ZERO,
-        }));
-    }
-
-    let today = query.date.unwrap_or_else(|| chrono::Utc::now().date_naive());
-    let today_str = today.format("%Y-%m-%d").to_string();
-
-    let start = today - Duration::days(30);
-    let start_str = start.format("%Y-%m-%d").to_string();
-    let yesterday_str = (today - Duration::days(1)).format("%Y-%m-%d").to_string();
-
-    let daily_spending = budget_repo::daily_spending(&pool, user.id, &start_str, &yesterday_str).await?;
-
-    let mut carryover = Decimal::ZERO;
-    let mut date = start;
-    while date < today {
-        let date_str = date.format("%Y-%m-%d").to_string();
-        let spent = daily_spending
-            .iter()
-            .find(|(d, _)| d == &date_str)
-            .map(|(_, s)| *s)
-            .unwrap_or(Decimal::ZERO);
-        let diff = daily_budget - spent;
-        carryover += diff;
-        date += Duration::days(1);
-    }
-
-    let spent_today = budget_repo::spent_on_date(&pool, user.id, &today_str).await?;
-    let effective = daily_budget + carryover;
-    let remaining = effective - spent_today;
-
-    Ok(Json(EffectiveBudget {
-        daily_budget,
-        effective_budget_today: effective,
-        carried_over: carryover,
-        spent_today,
-        remaining_today: remaining,
-    }))
+    let date = query.date.unwrap_or_else(|| user.today());
+    let budget = budget_service::effective_budget(&pool, &user, date).await?;
+    Ok(Json(budget))
 }
 
 pub async fn set_budget(
@@ -73,9 +31,39 @@ pub async fn set_budget(
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     Json(body): Json<SetBudgetRequest>,
 ) -> Result<Json<BudgetSetting>, AppError> {
-    if body.daily_budget < Decimal::ZERO {
+    let setting = budget_service::set_budget(&pool, &user, &body).await?;
+    Ok(Json(setting))
+}
+
+pub async fn budget_history(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+) -> Result<Json<Vec<BudgetSetting>>, AppError> {
+    let history = budget_service::history(&pool, user.id).await?;
+    Ok(Json(history))
+}
+
+pub async fn set_category_budget(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Json(body): Json<SetCategoryBudgetRequest>,
+) -> Result<Json<CategoryBudget>, AppError> {
+    if body.monthly_limit < Decimal::ZERO {
         return Err(AppError::Validation("Budget cannot be negative".into()));
     }
-    let setting = budget_repo::upsert(&pool, user.id, body.daily_budget).await?;
-    Ok(Json(setting))
+    let category = category_service::ensure_exists(&pool, user.id, &body.category).await?;
+    let budget = budget_repo::upsert_category_budget(&pool, user.id, &category, body.monthly_limit).await?;
+    Ok(Json(budget))
+}
+
+pub async fn delete_category_budget(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(category): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if budget_repo::delete_category_budget(&pool, user.id, &normalize_name(&category)).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::NotFound("Category budget not found".into()))
+    }
 }
@@ -0,0 +1,48 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::category::*;
+use crate::errors::AppError;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::category_service;
+
+pub async fn list_categories(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+) -> Result<Json<Vec<Category>>, AppError> {
+    let categories = category_service::list_categories(&pool, user.id).await?;
+    Ok(Json(categories))
+}
+
+pub async fn create_category(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Json(input): Json<CreateCategory>,
+) -> Result<(StatusCode, Json<Category>), AppError> {
+    let category = category_service::create_category(&pool, user.id, &input).await?;
+    Ok((StatusCode::CREATED, Json(category)))
+}
+
+pub async fn update_category(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<UpdateCategory>,
+) -> Result<Json<Category>, AppError> {
+    let category = category_service::update_category(&pool, id, user.id, &input).await?;
+    Ok(Json(category))
+}
+
+pub async fn delete_category(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    category_service::delete_category(&pool, id, user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
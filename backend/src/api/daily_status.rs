@@ -27,11 +27,10 @@ pub async fn check_status(
     State(pool): State<PgPool>,
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
 ) -> Result<Json<DailyStatusResponse>, AppError> {
-    let submitted = daily_check_service::check_submitted_today(&pool, user.id).await?;
-    let today = chrono::Utc::now().date_naive();
+    let submitted = daily_check_service::check_submitted_today(&pool, &user).await?;
     Ok(Json(DailyStatusResponse {
         submitted,
-        date: today.to_string(),
+        date: user.today().to_string(),
     }))
 }
 
@@ -40,8 +39,7 @@ pub async fn submit_day(
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     Json(input): Json<SubmitRequest>,
 ) -> Result<Json<DailyStatusResponse>, AppError> {
-    daily_check_service::mark_submitted(&pool, user.id, input.date).await?;
-    let date = input.date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let date = daily_check_service::mark_submitted(&pool, &user, input.date).await?;
     Ok(Json(DailyStatusResponse {
         submitted: true,
         date: date.to_string(),
@@ -1,24 +1,44 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
     Extension, Json,
 };
+use std::sync::Arc;
+
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::config::Config;
+use crate::domain::anomaly::{AnomalyFilter, CreatedExpense, ExpenseAnomaly};
 use crate::domain::expense::*;
+use crate::domain::pagination::Page;
 use crate::errors::AppError;
 use crate::middleware::auth::AuthenticatedUser;
-use crate::services::expense_service;
+use crate::services::ocr::OcrEngine;
+use crate::services::{
+    anomaly_service, expense_service, export_service, import_service, receipt_service,
+};
 
 pub async fn create_expense(
-    State(pool): State<PgPool>,
+    State((pool, config)): State<(PgPool, Config)>,
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     Json(input): Json<CreateExpense>,
-) -> Result<(axum::http::StatusCode, Json<Expense>), AppError> {
-    let expense = expense_service::create_expense(&pool, user.id, &input).await?;
+) -> Result<(axum::http::StatusCode, Json<CreatedExpense>), AppError> {
+    let expense = expense_service::create_expense(&pool, &config, &user, &input).await?;
     Ok((axum::http::StatusCode::CREATED, Json(expense)))
 }
 
+pub async fn quick_add(
+    State((pool, config)): State<(PgPool, Config)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Json(input): Json<QuickAddRequest>,
+) -> Result<(StatusCode, Json<QuickAddResult>), AppError> {
+    let result = expense_service::quick_add(&pool, &config, &user, &input).await?;
+    let status = if result.created.is_some() { StatusCode::CREATED } else { StatusCode::OK };
+    Ok((status, Json(result)))
+}
+
 pub async fn get_expense(
     State(pool): State<PgPool>,
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
@@ -32,18 +52,27 @@ pub async fn list_expenses(
     State(pool): State<PgPool>,
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     Query(filter): Query<ExpenseFilter>,
-) -> Result<Json<Vec<Expense>>, AppError> {
+) -> Result<Json<Page<Expense>>, AppError> {
     let expenses = expense_service::list_expenses(&pool, user.id, &filter).await?;
     Ok(Json(expenses))
 }
 
-pub async fn update_expense(
+pub async fn search_expenses(
     State(pool): State<PgPool>,
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Query(search): Query<ExpenseSearch>,
+) -> Result<Json<ExpenseSearchResult>, AppError> {
+    let result = expense_service::search_expenses(&pool, user.id, &search).await?;
+    Ok(Json(result))
+}
+
+pub async fn update_expense(
+    State((pool, config)): State<(PgPool, Config)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     Path(id): Path<Uuid>,
     Json(input): Json<UpdateExpense>,
 ) -> Result<Json<Expense>, AppError> {
-    let expense = expense_service::update_expense(&pool, id, user.id, &input).await?;
+    let expense = expense_service::update_expense(&pool, &config, id, &user, &input).await?;
     Ok(Json(expense))
 }
 
@@ -55,3 +84,104 @@ pub async fn delete_expense(
     expense_service::delete_expense(&pool, id, user.id).await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
+
+pub async fn list_anomalies(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Query(filter): Query<AnomalyFilter>,
+) -> Result<Json<Vec<ExpenseAnomaly>>, AppError> {
+    let anomalies = anomaly_service::list_anomalies(&pool, user.id, &filter).await?;
+    Ok(Json(anomalies))
+}
+
+pub async fn export_expenses(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Query(query): Query<ExportQuery>,
+    Query(filter): Query<ExpenseFilter>,
+) -> Result<impl IntoResponse, AppError> {
+    let (content_type, ext) = match query.format {
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        ExportFormat::Json => ("application/json", "json"),
+    };
+    let filename = format!("expenses-{}.{}", user.today(), ext);
+    let body = export_service::export_expenses(pool, user.id, filter, query.format);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    ))
+}
+
+pub async fn import_expenses(
+    State((pool, config)): State<(PgPool, Config)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Query(query): Query<ImportQuery>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<ImportReport>), AppError> {
+    let data = read_upload(multipart).await?;
+    let report = import_service::import_csv(&pool, &config, &user, &data, &query).await?;
+    Ok((import_status(&report), Json(report)))
+}
+
+pub async fn import_statement(
+    State((pool, config)): State<(PgPool, Config)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Query(query): Query<ImportQuery>,
+    Query(statement): Query<StatementImportQuery>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<ImportReport>), AppError> {
+    let data = read_upload(multipart).await?;
+    let report =
+        import_service::import_statement(&pool, &config, &user, &data, &statement, &query).await?;
+    Ok((import_status(&report), Json(report)))
+}
+
+pub async fn scan_receipt(
+    State((pool, ocr)): State<(PgPool, Arc<dyn OcrEngine>)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    multipart: Multipart,
+) -> Result<Json<ReceiptScan>, AppError> {
+    let image = read_upload(multipart).await?;
+    let scan = receipt_service::scan_receipt(&pool, ocr, &user, image).await?;
+    Ok(Json(scan))
+}
+
+/// Read the multipart field named "file".
+async fn read_upload(mut multipart: Multipart) -> Result<Vec<u8>, AppError> {
+    let mut file: Option<Vec<u8>> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Validation(format!("Multipart error: {}", e)))?
+    {
+        if field.name() == Some("file") {
+            file = Some(
+                field
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError::Validation(format!("Read error: {}", e)))?
+                    .to_vec(),
+            );
+        }
+    }
+
+    file.ok_or_else(|| AppError::Validation("No file field in upload".into()))
+}
+
+fn import_status(report: &ImportReport) -> StatusCode {
+    if report.dry_run {
+        StatusCode::OK
+    } else if report.committed {
+        StatusCode::CREATED
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+}
@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::goal::*;
+use crate::errors::AppError;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::goal_service;
+
+pub async fn create_goal(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Json(input): Json<CreateGoal>,
+) -> Result<(StatusCode, Json<GoalProgress>), AppError> {
+    let goal = goal_service::create_goal(&pool, &user, &input).await?;
+    Ok((StatusCode::CREATED, Json(goal)))
+}
+
+pub async fn list_goals(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+) -> Result<Json<Vec<GoalProgress>>, AppError> {
+    let goals = goal_service::list_goals(&pool, &user).await?;
+    Ok(Json(goals))
+}
+
+pub async fn get_goal(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<GoalProgress>, AppError> {
+    let goal = goal_service::get_goal(&pool, id, &user).await?;
+    Ok(Json(goal))
+}
+
+pub async fn update_goal(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<UpdateGoal>,
+) -> Result<Json<GoalProgress>, AppError> {
+    let goal = goal_service::update_goal(&pool, id, &user, &input).await?;
+    Ok(Json(goal))
+}
+
+pub async fn delete_goal(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    goal_service::delete_goal(&pool, id, user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn add_contribution(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(goal_id): Path<Uuid>,
+    Json(input): Json<CreateContribution>,
+) -> Result<(StatusCode, Json<GoalContribution>), AppError> {
+    let contribution = goal_service::add_contribution(&pool, goal_id, &user, &input).await?;
+    Ok((StatusCode::CREATED, Json(contribution)))
+}
+
+pub async fn list_contributions(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(goal_id): Path<Uuid>,
+) -> Result<Json<Vec<GoalContribution>>, AppError> {
+    let contributions = goal_service::list_contributions(&pool, goal_id, user.id).await?;
+    Ok(Json(contributions))
+}
+
+pub async fn delete_contribution(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path((goal_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    goal_service::delete_contribution(&pool, goal_id, id, &user).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::income::*;
+use crate::errors::AppError;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::income_service;
+
+pub async fn create_income(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Json(input): Json<CreateIncome>,
+) -> Result<(StatusCode, Json<Income>), AppError> {
+    let income = income_service::create_income(&pool, &user, &input).await?;
+    Ok((StatusCode::CREATED, Json(income)))
+}
+
+pub async fn list_incomes(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Query(filter): Query<IncomeFilter>,
+) -> Result<Json<Vec<Income>>, AppError> {
+    let incomes = income_service::list_incomes(&pool, user.id, &filter).await?;
+    Ok(Json(incomes))
+}
+
+pub async fn get_income(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Income>, AppError> {
+    let income = income_service::get_income(&pool, id, user.id).await?;
+    Ok(Json(income))
+}
+
+pub async fn update_income(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<UpdateIncome>,
+) -> Result<Json<Income>, AppError> {
+    let income = income_service::update_income(&pool, id, &user, &input).await?;
+    Ok(Json(income))
+}
+
+pub async fn delete_income(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    income_service::delete_income(&pool, id, user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
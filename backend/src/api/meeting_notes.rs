@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Multipart, Path, State},
+    extract::{Multipart, Path, Query, State},
     http::StatusCode,
     Extension, Json,
 };
@@ -7,6 +7,7 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::domain::meeting_note::*;
+use crate::domain::pagination::{decode_cursor, page_size, Page, PageQuery};
 use crate::errors::AppError;
 use crate::infrastructure::meeting_note_repo;
 use crate::middleware::auth::AuthenticatedUser;
@@ -24,9 +25,19 @@ pub async fn create_meeting_note(
 pub async fn list_meeting_notes(
     State(pool): State<PgPool>,
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
-) -> Result<Json<Vec<MeetingNote>>, AppError> {
-    let notes = meeting_note_repo::find_all(&pool, user.id).await?;
-    Ok(Json(notes))
+    Query(query): Query<PageQuery>,
+) -> Result<Json<Page<MeetingNote>>, AppError> {
+    let per_page = page_size(query.per_page);
+    let before: Option<MeetingNoteKey> = query
+        .cursor
+        .as_deref()
+        .map(decode_cursor)
+        .transpose()
+        .map_err(AppError::Validation)?;
+
+    let total = meeting_note_repo::count(&pool, user.id).await?;
+    let notes = meeting_note_repo::find_all(&pool, user.id, before, per_page + 1).await?;
+    Ok(Json(Page::from_rows(notes, per_page, total, MeetingNote::key)))
 }
 
 pub async fn get_meeting_note(
@@ -41,25 +52,11 @@ pub async fn get_meeting_note(
 }
 
 pub async fn delete_meeting_note(
-    State((pool, meeting_service)): State<(PgPool, Option<MeetingService>)>,
+    State((pool, _meeting_service)): State<(PgPool, Option<MeetingService>)>,
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, AppError> {
-    // Delete audio from S3 if exists
-    if let Some(service) = &meeting_service {
-        if let Some(note) = meeting_note_repo::find_by_id(&pool, id, user.id).await? {
-            if let Some(s3_key) = &note.audio_s3_key {
-                let s3_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-                let s3 = aws_sdk_s3::Client::new(&s3_config);
-                let _ = s3.delete_object()
-                    .bucket(service.bucket())
-                    .key(s3_key)
-                    .send()
-                    .await;
-            }
-        }
-    }
-
+    // Audio and photos stay in S3 until the trash is purged
     let deleted = meeting_note_repo::delete(&pool, id, user.id).await?;
     if deleted {
         Ok(StatusCode::NO_CONTENT)
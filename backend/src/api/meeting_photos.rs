@@ -75,16 +75,7 @@ pub async fn upload_photo(
         ));
 
     // Upload to S3
-    let s3_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-    let s3 = aws_sdk_s3::Client::new(&s3_config);
-    s3.put_object()
-        .bucket(service.bucket())
-        .key(&s3_key)
-        .body(aws_sdk_s3::primitives::ByteStream::from(data))
-        .content_type(&content_type)
-        .send()
-        .await
-        .map_err(|e| AppError::Internal(format!("S3 upload failed: {}", e)))?;
+    service.storage().put(&s3_key, data, &content_type).await?;
 
     let photo = meeting_photo_repo::create(
         &pool,
@@ -126,18 +117,16 @@ pub async fn list_photos(
 pub async fn delete_photo(
     State((pool, meeting_service)): State<(PgPool, Option<MeetingService>)>,
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
-    Path((_meeting_id, photo_id)): Path<(Uuid, Uuid)>,
+    Path((meeting_id, photo_id)): Path<(Uuid, Uuid)>,
 ) -> Result<StatusCode, AppError> {
-    if let Some(s3_key) = meeting_photo_repo::delete(&pool, photo_id, user.id).await? {
+    // A trashed note keeps its photos until it is restored or purged
+    meeting_note_repo::find_by_id(&pool, meeting_id, user.id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Meeting note not found".into()))?;
+
+    if let Some(s3_key) = meeting_photo_repo::delete(&pool, photo_id, meeting_id, user.id).await? {
         if let Some(service) = &meeting_service {
-            let s3_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-            let s3 = aws_sdk_s3::Client::new(&s3_config);
-            let _ = s3
-                .delete_object()
-                .bucket(service.bucket())
-                .key(&s3_key)
-                .send()
-                .await;
+            let _ = service.storage().delete(&s3_key).await;
         }
         Ok(StatusCode::NO_CONTENT)
     } else {
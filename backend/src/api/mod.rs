@@ -6,3 +6,10 @@ pub mod daily_status;
 pub mod meeting_notes;
 pub mod meeting_photos;
 pub mod budget;
+pub mod categories;
+pub mod recurring_expenses;
+pub mod incomes;
+pub mod goals;
+pub mod attachments;
+pub mod sync;
+pub mod trash;
@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::domain::recurring_expense::*;
+use crate::errors::AppError;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::recurring_expense_service;
+
+pub async fn create_recurring_expense(
+    State((pool, config)): State<(PgPool, Config)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Json(input): Json<CreateRecurringExpense>,
+) -> Result<(StatusCode, Json<RecurringExpense>), AppError> {
+    let recurring = recurring_expense_service::create_recurring_expense(&pool, &config, &user, &input).await?;
+    Ok((StatusCode::CREATED, Json(recurring)))
+}
+
+pub async fn list_recurring_expenses(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+) -> Result<Json<Vec<RecurringExpense>>, AppError> {
+    let recurring = recurring_expense_service::list_recurring_expenses(&pool, user.id).await?;
+    Ok(Json(recurring))
+}
+
+pub async fn get_recurring_expense(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RecurringExpense>, AppError> {
+    let recurring = recurring_expense_service::get_recurring_expense(&pool, id, user.id).await?;
+    Ok(Json(recurring))
+}
+
+pub async fn update_recurring_expense(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<UpdateRecurringExpense>,
+) -> Result<Json<RecurringExpense>, AppError> {
+    let recurring = recurring_expense_service::update_recurring_expense(&pool, id, &user, &input).await?;
+    Ok(Json(recurring))
+}
+
+pub async fn delete_recurring_expense(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    recurring_expense_service::delete_recurring_expense(&pool, id, user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,17 @@
+use axum::{extract::State, Extension, Json};
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::domain::sync::{SyncRequest, SyncResponse};
+use crate::errors::AppError;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::sync_service;
+
+pub async fn sync(
+    State((pool, config)): State<(PgPool, Config)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Json(request): Json<SyncRequest>,
+) -> Result<Json<SyncResponse>, AppError> {
+    let response = sync_service::sync(&pool, &config, &user, &request).await?;
+    Ok(Json(response))
+}
@@ -0,0 +1,40 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::domain::expense::Expense;
+use crate::domain::meeting_note::MeetingNote;
+use crate::domain::trash::Trash;
+use crate::errors::AppError;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::trash_service;
+
+pub async fn list_trash(
+    State((pool, config)): State<(PgPool, Config)>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+) -> Result<Json<Trash>, AppError> {
+    let trash = trash_service::list_trash(&pool, user.id, config.trash_retention_days).await?;
+    Ok(Json(trash))
+}
+
+pub async fn restore_expense(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Expense>, AppError> {
+    let expense = trash_service::restore_expense(&pool, id, user.id).await?;
+    Ok(Json(expense))
+}
+
+pub async fn restore_meeting_note(
+    State(pool): State<PgPool>,
+    Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<MeetingNote>, AppError> {
+    let note = trash_service::restore_meeting_note(&pool, id, user.id).await?;
+    Ok(Json(note))
+}
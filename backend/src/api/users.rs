@@ -1,22 +1,35 @@
 use axum::{extract::State, Extension, Json};
+use chrono_tz::Tz;
 use sqlx::PgPool;
 
 use crate::domain::user::UpdateUserProfile;
 use crate::errors::AppError;
 use crate::infrastructure::user_repo;
 use crate::middleware::auth::AuthenticatedUser;
+use crate::services::currency_service;
 
 pub async fn update_profile(
     State(pool): State<PgPool>,
     Extension(AuthenticatedUser(user)): Extension<AuthenticatedUser>,
-    Json(input): Json<UpdateUserProfile>,
+    Json(mut input): Json<UpdateUserProfile>,
 ) -> Result<Json<serde_json::Value>, AppError> {
+    if let Some(tz) = &input.timezone {
+        tz.parse::<Tz>()
+            .map_err(|_| AppError::Validation(format!("Invalid timezone: {}", tz)))?;
+    }
+    if let Some(code) = &input.base_currency {
+        input.base_currency =
+            Some(currency_service::validate_base_currency(&pool, &user, code).await?);
+    }
     let updated = user_repo::update_profile(&pool, user.id, &input).await?;
     Ok(Json(serde_json::json!({
         "id": updated.id,
         "email": updated.email,
         "name": updated.name,
         "notification_time": updated.notification_time.to_string(),
+        "timezone": updated.timezone,
+        "base_currency": updated.base_currency,
+        "anomaly_alerts": updated.anomaly_alerts,
         "created_at": updated.created_at,
     })))
 }
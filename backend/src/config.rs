@@ -10,6 +10,16 @@ pub struct Config {
     pub cognito_app_client_id: String,
     pub sns_platform_application_arn: Option<String>,
     pub meeting_audio_s3_bucket: Option<String>,
+    /// Bucket for expense attachments; defaults to the meeting audio bucket.
+    pub attachments_s3_bucket: Option<String>,
+    /// Endpoint of an S3-compatible server (e.g. MinIO) to use instead of AWS.
+    pub s3_endpoint_url: Option<String>,
+    /// Path to the tesseract binary used to read receipt photos.
+    pub tesseract_path: String,
+    /// Tesseract languages, e.g. `eng` or `eng+hin`.
+    pub ocr_languages: String,
+    /// Days deleted expenses and meeting notes stay in the trash; at least 1.
+    pub trash_retention_days: i32,
 }
 
 impl Config {
@@ -31,6 +41,20 @@ impl Config {
                 .expect("COGNITO_APP_CLIENT_ID must be set"),
             sns_platform_application_arn: std::env::var("SNS_PLATFORM_APPLICATION_ARN").ok(),
             meeting_audio_s3_bucket: std::env::var("MEETING_AUDIO_S3_BUCKET").ok(),
+            attachments_s3_bucket: std::env::var("ATTACHMENTS_S3_BUCKET")
+                .or_else(|_| std::env::var("MEETING_AUDIO_S3_BUCKET"))
+                .ok(),
+            s3_endpoint_url: std::env::var("S3_ENDPOINT_URL").ok(),
+            tesseract_path: std::env::var("TESSERACT_PATH")
+                .unwrap_or_else(|_| "tesseract".to_string()),
+            ocr_languages: std::env::var("OCR_LANGUAGES").unwrap_or_else(|_| "eng".to_string()),
+            trash_retention_days: std::env::var("TRASH_RETENTION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .ok()
+                // Anything less would purge the trash on the next run
+                .filter(|days: &i32| *days >= 1)
+                .expect("TRASH_RETENTION_DAYS must be a number of days, at least 1"),
         })
     }
 
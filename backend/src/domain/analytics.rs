@@ -0,0 +1,226 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::expense::{CategorySummary, ExpenseSummary};
+
+/// Period length for time-bucketed analytics. Weeks start on Monday.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Bucket {
+    Day,
+    Week,
+    #[default]
+    Month,
+}
+
+impl Bucket {
+    /// The unit name understood by Postgres `date_trunc` and intervals.
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Bucket::Day => "day",
+            Bucket::Week => "week",
+            Bucket::Month => "month",
+        }
+    }
+
+    /// The first day of the bucket containing `date`.
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Bucket::Day => date,
+            Bucket::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Bucket::Month => date.with_day(1).unwrap(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CashflowQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub bucket: Bucket,
+}
+
+/// Income and expense totals for one bucket, before net and savings rate.
+#[derive(Debug, sqlx::FromRow)]
+pub struct CashflowTotals {
+    pub period_start: NaiveDate,
+    pub income: Decimal,
+    pub expenses: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CashflowPeriod {
+    pub period_start: NaiveDate,
+    pub income: Decimal,
+    pub expenses: Decimal,
+    pub net: Decimal,
+    /// Net savings as a percentage of income; `None` when there was no income.
+    pub savings_rate: Option<Decimal>,
+}
+
+impl From<CashflowTotals> for CashflowPeriod {
+    fn from(t: CashflowTotals) -> Self {
+        let net = t.income - t.expenses;
+        Self {
+            period_start: t.period_start,
+            income: t.income,
+            expenses: t.expenses,
+            net,
+            savings_rate: savings_rate(t.income, net),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CashflowReport {
+    pub currency: String,
+    pub bucket: Bucket,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub total_income: Decimal,
+    pub total_expenses: Decimal,
+    pub net: Decimal,
+    pub savings_rate: Option<Decimal>,
+    pub periods: Vec<CashflowPeriod>,
+}
+
+pub fn savings_rate(income: Decimal, net: Decimal) -> Option<Decimal> {
+    (income > Decimal::ZERO).then(|| (net / income * Decimal::ONE_HUNDRED).round_dp(2))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimeseriesQuery {
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    #[serde(default)]
+    pub bucket: Bucket,
+    pub category: Option<String>,
+}
+
+/// One (bucket, category) total. Empty buckets have a single row with no
+/// category.
+#[derive(Debug, sqlx::FromRow)]
+pub struct TimeseriesRow {
+    pub period_start: NaiveDate,
+    pub category: Option<String>,
+    pub total: Decimal,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeseriesBucket {
+    pub period_start: NaiveDate,
+    pub total: Decimal,
+    pub count: i64,
+    pub by_category: Vec<CategorySummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Timeseries {
+    pub currency: String,
+    pub bucket: Bucket,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub category: Option<String>,
+    pub total: Decimal,
+    pub count: i64,
+    pub buckets: Vec<TimeseriesBucket>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompareQuery {
+    /// Any day in the current period; defaults to today.
+    pub date: Option<NaiveDate>,
+    /// Only compare up to the same day of each period, e.g. the 1st to the
+    /// 10th of this month against the 1st to the 10th of last month.
+    #[serde(default)]
+    pub to_date: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PeriodSummary {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    #[serde(flatten)]
+    pub summary: ExpenseSummary,
+}
+
+/// Change from an earlier amount to the current one. `percent` is `None`
+/// when the earlier amount was zero.
+#[derive(Debug, Serialize)]
+pub struct Delta {
+    pub absolute: Decimal,
+    pub percent: Option<Decimal>,
+}
+
+impl Delta {
+    pub fn between(earlier: Decimal, current: Decimal) -> Self {
+        let absolute = current - earlier;
+        let percent = (earlier != Decimal::ZERO)
+            .then(|| (absolute / earlier * Decimal::ONE_HUNDRED).round_dp(2));
+        Self { absolute, percent }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryComparison {
+    pub category: String,
+    pub current: Decimal,
+    pub previous: Decimal,
+    pub last_year: Decimal,
+    pub vs_previous: Delta,
+    pub vs_last_year: Delta,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PeriodComparison {
+    pub currency: String,
+    pub current: PeriodSummary,
+    pub previous: PeriodSummary,
+    pub last_year: PeriodSummary,
+    pub vs_previous: Delta,
+    pub vs_last_year: Delta,
+    pub by_category: Vec<CategoryComparison>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForecastQuery {
+    /// Forecast as of this day; defaults to today.
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Forecast {
+    pub currency: String,
+    pub month_start: NaiveDate,
+    pub month_end: NaiveDate,
+    pub as_of: NaiveDate,
+    pub days_elapsed: i64,
+    pub days_remaining: i64,
+    pub spent_to_date: Decimal,
+    pub daily_average: Decimal,
+    pub projected_total: Decimal,
+    /// 95% interval for the month-end total, from day-to-day variation so far.
+    pub projected_low: Decimal,
+    pub projected_high: Decimal,
+    /// Daily budget times the days in the month, if a daily budget is set.
+    pub monthly_budget: Option<Decimal>,
+    pub projected_remaining: Option<Decimal>,
+    /// The day the monthly budget runs out at the current pace, or was
+    /// already used up. `None` if it lasts the month.
+    pub budget_exhausted_on: Option<NaiveDate>,
+    pub confidence: Confidence,
+    /// Between 0 and 1; grows with the share of the month observed and
+    /// shrinks as daily spending varies more.
+    pub confidence_score: Decimal,
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::expense::Expense;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyRule {
+    /// More than three standard deviations above the category mean.
+    StdDev,
+    /// Above the category's 95th percentile and at least twice its mean.
+    Percentile,
+}
+
+impl std::fmt::Display for AnomalyRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnomalyRule::StdDev => write!(f, "std_dev"),
+            AnomalyRule::Percentile => write!(f, "percentile"),
+        }
+    }
+}
+
+/// Distribution of a user's past expense amounts in one category, in the
+/// base currency.
+#[derive(Debug, sqlx::FromRow)]
+pub struct AmountStats {
+    pub sample_size: i64,
+    pub mean: Option<Decimal>,
+    pub std_dev: Option<Decimal>,
+    pub p95: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ExpenseAnomaly {
+    pub id: Uuid,
+    pub expense_id: Uuid,
+    pub user_id: Uuid,
+    pub category: String,
+    pub amount: Decimal,
+    pub rule: String,
+    pub reason: String,
+    pub mean: Decimal,
+    pub std_dev: Decimal,
+    pub p95: Decimal,
+    pub sample_size: i32,
+    pub expense_date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Why an expense was flagged. Amounts are in the base currency.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnomalyFlag {
+    pub rule: AnomalyRule,
+    pub reason: String,
+    pub amount: Decimal,
+    pub typical_amount: Decimal,
+    pub std_dev: Decimal,
+    pub p95: Decimal,
+    pub sample_size: i64,
+}
+
+/// A newly created expense, flagged if it is unusually large.
+#[derive(Debug, Serialize)]
+pub struct CreatedExpense {
+    #[serde(flatten)]
+    pub expense: Expense,
+    pub is_anomaly: bool,
+    pub anomaly: Option<AnomalyFlag>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnomalyFilter {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub category: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ExpenseAttachment {
+    pub id: Uuid,
+    pub expense_id: Uuid,
+    pub user_id: Uuid,
+    pub s3_key: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An attachment with a presigned download URL, valid for an hour.
+#[derive(Debug, Serialize)]
+pub struct AttachmentLink {
+    #[serde(flatten)]
+    pub attachment: ExpenseAttachment,
+    pub url: String,
+}
+
+/// A file read from a multipart upload.
+#[derive(Debug)]
+pub struct AttachmentUpload {
+    pub file_name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
@@ -1,27 +1,173 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    #[default]
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl std::fmt::Display for BudgetPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BudgetPeriod::Daily => write!(f, "daily"),
+            BudgetPeriod::Weekly => write!(f, "weekly"),
+            BudgetPeriod::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+impl std::str::FromStr for BudgetPeriod {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(BudgetPeriod::Daily),
+            "weekly" => Ok(BudgetPeriod::Weekly),
+            "monthly" => Ok(BudgetPeriod::Monthly),
+            _ => Err(format!("Invalid budget period: {}", s)),
+        }
+    }
+}
+
+impl BudgetPeriod {
+    /// First and last day of the period containing `date`. Weeks run Monday to Sunday.
+    pub fn bounds(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            BudgetPeriod::Daily => (date, date),
+            BudgetPeriod::Weekly => {
+                let start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                (start, start + Duration::days(6))
+            }
+            BudgetPeriod::Monthly => {
+                let start = date.with_day(1).unwrap();
+                (start, start + Months::new(1) - Duration::days(1))
+            }
+        }
+    }
+
+    /// How many past periods are carried over when the user hasn't chosen:
+    /// a month of days, four weeks, or just last month.
+    pub fn default_carry_over_periods(&self) -> u32 {
+        match self {
+            BudgetPeriod::Daily => 30,
+            BudgetPeriod::Weekly => 4,
+            BudgetPeriod::Monthly => 1,
+        }
+    }
+}
+
+/// How unspent or overspent budget from earlier periods moves forward.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CarryOver {
+    /// Savings and overspend both carry forward
+    #[default]
+    Full,
+    /// Every period starts fresh
+    None,
+    /// Only unspent budget carries forward; overspend is forgiven
+    Savings,
+    /// Only overspend carries forward; savings are dropped
+    Overspend,
+    /// Both carry forward, limited to `carry_over_cap` either way
+    Capped,
+}
+
+impl std::fmt::Display for CarryOver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CarryOver::Full => write!(f, "full"),
+            CarryOver::None => write!(f, "none"),
+            CarryOver::Savings => write!(f, "savings"),
+            CarryOver::Overspend => write!(f, "overspend"),
+            CarryOver::Capped => write!(f, "capped"),
+        }
+    }
+}
+
+impl std::str::FromStr for CarryOver {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "full" => Ok(CarryOver::Full),
+            "none" => Ok(CarryOver::None),
+            "savings" => Ok(CarryOver::Savings),
+            "overspend" => Ok(CarryOver::Overspend),
+            "capped" => Ok(CarryOver::Capped),
+            _ => Err(format!("Invalid carry-over rule: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct BudgetSetting {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub daily_budget: Decimal,
+    pub amount: Decimal,
+    pub period: String,
+    pub carry_over: String,
+    pub carry_over_cap: Option<Decimal>,
+    pub carry_over_periods: Option<i32>,
+    /// First day this setting applies to; it stays in force until the next change.
+    pub effective_from: NaiveDate,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SetBudgetRequest {
-    pub daily_budget: Decimal,
+    /// Budget per period. Older clients send this as `daily_budget`.
+    #[serde(alias = "daily_budget")]
+    pub amount: Decimal,
+    #[serde(default)]
+    pub period: BudgetPeriod,
+    #[serde(default)]
+    pub carry_over: CarryOver,
+    pub carry_over_cap: Option<Decimal>,
+    pub carry_over_periods: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct EffectiveBudget {
-    pub daily_budget: Decimal,
-    pub effective_budget_today: Decimal,
+    pub period: BudgetPeriod,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub budget: Decimal,
+    pub carry_over: CarryOver,
     pub carried_over: Decimal,
+    pub effective_budget: Decimal,
+    pub spent: Decimal,
+    pub remaining: Decimal,
     pub spent_today: Decimal,
-    pub remaining_today: Decimal,
+    pub categories: Vec<CategoryBudgetStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CategoryBudget {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub category: String,
+    pub monthly_limit: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetCategoryBudgetRequest {
+    pub category: String,
+    pub monthly_limit: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryBudgetStatus {
+    pub category: String,
+    pub monthly_limit: Decimal,
+    pub spent: Decimal,
+    pub remaining: Decimal,
+    pub percent_used: Decimal,
 }
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::patch::present;
+
+/// Categories every new user starts with: (name, icon, color).
+pub const DEFAULT_CATEGORIES: &[(&str, &str, &str)] = &[
+    ("food", "restaurant", "#ef4444"),
+    ("entertainment", "movie", "#8b5cf6"),
+    ("travel", "flight", "#3b82f6"),
+    ("bills", "receipt", "#f59e0b"),
+    ("shopping", "shopping_bag", "#10b981"),
+    ("other", "category", "#6b7280"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Category {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCategory {
+    pub name: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCategory {
+    pub name: Option<String>,
+    /// `Some(None)` when sent as `null`, to clear the icon.
+    #[serde(default, deserialize_with = "present")]
+    pub icon: Option<Option<String>>,
+    /// `Some(None)` when sent as `null`, to clear the color.
+    #[serde(default, deserialize_with = "present")]
+    pub color: Option<Option<String>>,
+}
+
+/// Category names are stored trimmed and lowercased, e.g. "Groceries " -> "groceries".
+pub fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Category name cannot be empty".to_string());
+    }
+    if name.chars().count() > 50 {
+        return Err("Category name must be at most 50 characters".to_string());
+    }
+    Ok(())
+}
+
+/// Colors are `#rrggbb` hex strings.
+pub fn validate_color(color: &str) -> Result<(), String> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid color: {}", color))
+    }
+}
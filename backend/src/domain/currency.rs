@@ -0,0 +1,9 @@
+/// Currency codes are three-letter ISO 4217 codes, stored in upper case.
+pub fn normalize_code(code: &str) -> Result<String, String> {
+    let code = code.trim().to_uppercase();
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()) {
+        Ok(code)
+    } else {
+        Err(format!("Invalid currency code: {}", code))
+    }
+}
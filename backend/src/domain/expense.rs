@@ -3,83 +3,176 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type)]
-#[sqlx(type_name = "varchar", rename_all = "lowercase")]
-#[serde(rename_all = "lowercase")]
-pub enum Category {
-    Food,
-    Entertainment,
-    Travel,
-    Bills,
-    Shopping,
-    Other,
-}
-
-impl std::fmt::Display for Category {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Category::Food => write!(f, "food"),
-            Category::Entertainment => write!(f, "entertainment"),
-            Category::Travel => write!(f, "travel"),
-            Category::Bills => write!(f, "bills"),
-            Category::Shopping => write!(f, "shopping"),
-            Category::Other => write!(f, "other"),
-        }
-    }
-}
-
-impl std::str::FromStr for Category {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "food" => Ok(Category::Food),
-            "entertainment" => Ok(Category::Entertainment),
-            "travel" => Ok(Category::Travel),
-            "bills" => Ok(Category::Bills),
-            "shopping" => Ok(Category::Shopping),
-            "other" => Ok(Category::Other),
-            _ => Err(format!("Invalid category: {}", s)),
-        }
-    }
-}
+use crate::domain::anomaly::CreatedExpense;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Expense {
     pub id: Uuid,
     pub user_id: Uuid,
     pub amount: Decimal,
+    pub currency: String,
     pub category: String,
     pub note: Option<String>,
+    pub tags: Vec<String>,
     pub expense_date: NaiveDate,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateExpense {
     pub amount: Decimal,
-    pub category: Category,
+    /// Defaults to the user's base currency.
+    #[serde(default)]
+    pub currency: Option<String>,
+    pub category: String,
     pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub expense_date: Option<NaiveDate>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct UpdateExpense {
     pub amount: Option<Decimal>,
-    pub category: Option<Category>,
+    pub currency: Option<String>,
+    pub category: Option<String>,
     pub note: Option<String>,
+    /// Replaces all of the expense's tags.
+    pub tags: Option<Vec<String>>,
     pub expense_date: Option<NaiveDate>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ExpenseFilter {
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
-    pub category: Option<Category>,
+    pub category: Option<String>,
+    /// `next_cursor` from the previous page.
+    pub cursor: Option<String>,
+    pub per_page: Option<i64>,
+}
+
+/// Expenses are paged and exported in `(expense_date, created_at, id)` order.
+pub type ExpenseKey = (NaiveDate, DateTime<Utc>, Uuid);
+
+impl Expense {
+    pub fn key(&self) -> ExpenseKey {
+        (self.expense_date, self.created_at, self.id)
+    }
+}
+
+/// Query for `GET /expenses/search`. List parameters are comma-separated.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpenseSearch {
+    /// Full-text query over notes and tags, e.g. `coffee -starbucks`.
+    pub q: Option<String>,
+    /// Expenses must have all of these tags.
+    pub tags: Option<String>,
+    /// Expenses may be in any of these categories.
+    pub categories: Option<String>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
     pub page: Option<i64>,
     pub per_page: Option<i64>,
 }
 
+/// `ExpenseSearch` with its lists split and normalized.
+#[derive(Debug, Default)]
+pub struct ExpenseSearchCriteria {
+    pub q: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExpenseSearchResult {
+    /// Matching expenses across all pages.
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub expenses: Vec<Expense>,
+}
+
+/// Most tags one expense can have.
+pub const MAX_TAGS: usize = 10;
+
+/// Tags are stored lowercased without a leading '#', e.g. "#Work-Trip" ->
+/// "work-trip". Duplicates are dropped.
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        if tag.is_empty() || normalized.contains(&tag) {
+            continue;
+        }
+        if tag.chars().count() > 30
+            || !tag.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Invalid tag: {} (use up to 30 letters, digits, '-' or '_')",
+                tag
+            ));
+        }
+        normalized.push(tag);
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(format!("An expense can have at most {} tags", MAX_TAGS));
+    }
+    Ok(normalized)
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// One exported expense. Field order is the CSV column order and must stay stable.
+#[derive(Debug, Serialize)]
+pub struct ExpenseExportRow {
+    pub id: Uuid,
+    pub expense_date: NaiveDate,
+    pub amount: String,
+    pub currency: String,
+    pub category: String,
+    pub note: Option<String>,
+    /// Comma-separated, so the row stays flat in CSV.
+    pub tags: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Expense> for ExpenseExportRow {
+    fn from(e: &Expense) -> Self {
+        Self {
+            id: e.id,
+            expense_date: e.expense_date,
+            amount: format!("{:.2}", e.amount),
+            currency: e.currency.clone(),
+            category: e.category.clone(),
+            note: e.note.clone(),
+            tags: e.tags.join(","),
+            created_at: e.created_at,
+            updated_at: e.updated_at,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct DailySubmission {
     pub id: Uuid,
@@ -101,3 +194,106 @@ pub struct CategorySummary {
     pub total: Decimal,
     pub count: i64,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub include_duplicates: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementFormat {
+    Ofx,
+    Qif,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatementImportQuery {
+    /// Detected from the file contents when omitted.
+    pub format: Option<StatementFormat>,
+    /// Read QIF dates as day/month instead of month/day.
+    #[serde(default)]
+    pub day_first: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowStatus {
+    Valid,
+    Invalid,
+    Duplicate,
+    /// The bank transaction was imported from an earlier statement. These
+    /// rows are never inserted, even with `include_duplicates`.
+    AlreadyImported,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportRowResult {
+    pub line: usize,
+    pub status: ImportRowStatus,
+    pub errors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fitid: Option<String>,
+    pub expense: Option<CreateExpense>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub committed: bool,
+    pub total_rows: usize,
+    pub valid_rows: usize,
+    pub invalid_rows: usize,
+    pub duplicate_rows: usize,
+    pub already_imported_rows: usize,
+    pub inserted: usize,
+    pub rows: Vec<ImportRowResult>,
+}
+
+/// What was read off a receipt photo. `draft` is filled in when a total was
+/// found, for the user to check and send to `POST /expenses`.
+#[derive(Debug, Serialize)]
+pub struct ReceiptScan {
+    pub merchant: Option<String>,
+    pub total: Option<Decimal>,
+    pub date: Option<NaiveDate>,
+    pub draft: Option<CreateExpense>,
+    /// The raw OCR text, to help correct a bad read.
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuickAddRequest {
+    /// Free text such as "120 coffee yesterday".
+    pub text: String,
+    /// Save the parsed expense instead of only returning it.
+    #[serde(default)]
+    pub commit: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+/// How sure the quick-add parser is about each field it filled in.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct QuickAddConfidence {
+    pub amount: Confidence,
+    pub category: Confidence,
+    pub date: Confidence,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuickAddResult {
+    pub expense: CreateExpense,
+    pub confidence: QuickAddConfidence,
+    /// The saved expense, when `commit` was set.
+    pub created: Option<CreatedExpense>,
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::patch::present;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContributionSource {
+    Manual,
+    /// Saved from a budget period's unspent amount by the scheduler
+    Auto,
+}
+
+impl std::fmt::Display for ContributionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContributionSource::Manual => write!(f, "manual"),
+            ContributionSource::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SavingsGoal {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub target_amount: Decimal,
+    pub start_date: NaiveDate,
+    pub deadline: Option<NaiveDate>,
+    pub auto_contribute_percent: i32,
+    pub achieved_on: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateGoal {
+    pub name: String,
+    pub target_amount: Decimal,
+    pub deadline: Option<NaiveDate>,
+    #[serde(default)]
+    pub auto_contribute_percent: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateGoal {
+    pub name: Option<String>,
+    pub target_amount: Option<Decimal>,
+    /// `Some(None)` when sent as `null`, to clear the deadline.
+    #[serde(default, deserialize_with = "present")]
+    pub deadline: Option<Option<NaiveDate>>,
+    pub auto_contribute_percent: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct GoalContribution {
+    pub id: Uuid,
+    pub goal_id: Uuid,
+    pub amount: Decimal,
+    pub source: String,
+    pub period_start: Option<NaiveDate>,
+    pub note: Option<String>,
+    pub contributed_on: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateContribution {
+    pub amount: Decimal,
+    pub note: Option<String>,
+    pub contributed_on: Option<NaiveDate>,
+}
+
+/// A goal with how far along it is, as of the user's today.
+#[derive(Debug, Serialize)]
+pub struct GoalProgress {
+    #[serde(flatten)]
+    pub goal: SavingsGoal,
+    pub saved: Decimal,
+    pub remaining: Decimal,
+    pub percent_complete: Decimal,
+    /// Days left including today; `None` without a deadline.
+    pub days_left: Option<i64>,
+    /// Saving needed each remaining day to reach the target by the deadline.
+    pub required_daily_rate: Option<Decimal>,
+    /// Average saved per day since the goal started.
+    pub current_daily_rate: Decimal,
+    /// When the target is reached at `current_daily_rate`.
+    pub projected_completion: Option<NaiveDate>,
+    /// Whether the amount saved keeps pace with an even schedule to the
+    /// deadline. `None` without a deadline.
+    pub on_track: Option<bool>,
+}
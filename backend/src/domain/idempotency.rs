@@ -0,0 +1,15 @@
+/// How long a stored response is replayed for.
+pub const KEY_TTL_HOURS: i32 = 24;
+
+/// Longest accepted `Idempotency-Key` header.
+pub const MAX_KEY_LEN: usize = 255;
+
+/// The first request made with an idempotency key, and its response once
+/// it has finished.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    pub status_code: Option<i16>,
+    pub content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+}
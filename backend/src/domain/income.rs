@@ -0,0 +1,73 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IncomeSource {
+    Salary,
+    Freelance,
+    Business,
+    Investment,
+    Rental,
+    Gift,
+    Refund,
+    Other,
+}
+
+impl std::fmt::Display for IncomeSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IncomeSource::Salary => write!(f, "salary"),
+            IncomeSource::Freelance => write!(f, "freelance"),
+            IncomeSource::Business => write!(f, "business"),
+            IncomeSource::Investment => write!(f, "investment"),
+            IncomeSource::Rental => write!(f, "rental"),
+            IncomeSource::Gift => write!(f, "gift"),
+            IncomeSource::Refund => write!(f, "refund"),
+            IncomeSource::Other => write!(f, "other"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Income {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub source: String,
+    pub note: Option<String>,
+    pub income_date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateIncome {
+    pub amount: Decimal,
+    /// Defaults to the user's base currency.
+    pub currency: Option<String>,
+    pub source: IncomeSource,
+    pub note: Option<String>,
+    pub income_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateIncome {
+    pub amount: Option<Decimal>,
+    pub currency: Option<String>,
+    pub source: Option<IncomeSource>,
+    pub note: Option<String>,
+    pub income_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IncomeFilter {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub source: Option<IncomeSource>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
@@ -17,6 +17,15 @@ pub struct MeetingNote {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Meeting notes are paged newest first in `(created_at, id)` order.
+pub type MeetingNoteKey = (DateTime<Utc>, Uuid);
+
+impl MeetingNote {
+    pub fn key(&self) -> MeetingNoteKey {
+        (self.created_at, self.id)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateMeetingNote {
     pub meeting_title: Option<String>,
@@ -3,3 +3,17 @@ pub mod expense;
 pub mod meeting_note;
 pub mod meeting_photo;
 pub mod budget;
+pub mod reminder;
+pub mod category;
+pub mod recurring_expense;
+pub mod currency;
+pub mod income;
+pub mod analytics;
+pub mod anomaly;
+pub mod goal;
+pub mod attachment;
+pub mod pagination;
+pub mod sync;
+pub mod idempotency;
+pub mod trash;
+pub mod patch;
@@ -0,0 +1,97 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PageQuery {
+    /// `next_cursor` from the previous page; omit for the first page.
+    pub cursor: Option<String>,
+    pub per_page: Option<i64>,
+}
+
+/// One page of a listing in keyset order.
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Pass as `cursor` to get the next page; `null` on the last page.
+    pub next_cursor: Option<String>,
+    /// Items matching the filters across all pages.
+    pub total: i64,
+}
+
+impl<T> Page<T> {
+    /// Build a page from up to `per_page + 1` rows. The extra row is only
+    /// fetched to tell whether there is another page, and is dropped.
+    pub fn from_rows<K: Serialize>(
+        mut rows: Vec<T>,
+        per_page: i64,
+        total: i64,
+        key: impl Fn(&T) -> K,
+    ) -> Self {
+        let more = rows.len() as i64 > per_page;
+        rows.truncate(per_page as usize);
+        let next_cursor = rows.last().filter(|_| more).map(|last| encode_cursor(&key(last)));
+        Page { items: rows, next_cursor, total }
+    }
+}
+
+pub fn page_size(per_page: Option<i64>) -> i64 {
+    per_page.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+/// Cursors are the sort key of the last item on a page, as base64 JSON.
+/// Clients should treat them as opaque.
+pub fn encode_cursor<K: Serialize>(key: &K) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(key).unwrap_or_default())
+}
+
+pub fn decode_cursor<K: DeserializeOwned>(cursor: &str) -> Result<K, String> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or_else(|| "Invalid cursor".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, NaiveDate, Utc};
+    use uuid::Uuid;
+
+    type Key = (NaiveDate, DateTime<Utc>, Uuid);
+
+    #[test]
+    fn cursor_round_trip() {
+        let key: Key = (
+            NaiveDate::from_ymd_opt(2026, 2, 10).unwrap(),
+            "2026-02-10T12:00:00.123456Z".parse().unwrap(),
+            Uuid::new_v4(),
+        );
+        let cursor = encode_cursor(&key);
+        assert!(cursor.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(decode_cursor::<Key>(&cursor).unwrap(), key);
+    }
+
+    #[test]
+    fn invalid_cursors() {
+        assert!(decode_cursor::<Key>("not a cursor").is_err());
+        assert!(decode_cursor::<Key>(&encode_cursor(&"wrong shape")).is_err());
+    }
+
+    #[test]
+    fn extra_row_means_another_page() {
+        let page = Page::from_rows(vec![5, 4, 3], 2, 10, |n| *n);
+        assert_eq!(page.items, vec![5, 4]);
+        assert_eq!(page.total, 10);
+        assert_eq!(decode_cursor::<i32>(page.next_cursor.as_deref().unwrap()), Ok(4));
+
+        let last = Page::from_rows(vec![2, 1], 2, 10, |n| *n);
+        assert_eq!(last.items, vec![2, 1]);
+        assert_eq!(last.next_cursor, None);
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Deserializer};
+
+/// For optional fields of update requests that can be cleared: tells a field
+/// sent as `null` (`Some(None)`) apart from one left out (`None`). Use with
+/// `#[serde(default, deserialize_with = "present")]`.
+pub fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::patch::present;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl std::fmt::Display for Frequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Frequency::Daily => write!(f, "daily"),
+            Frequency::Weekly => write!(f, "weekly"),
+            Frequency::Monthly => write!(f, "monthly"),
+            Frequency::Yearly => write!(f, "yearly"),
+        }
+    }
+}
+
+impl std::str::FromStr for Frequency {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(Frequency::Daily),
+            "weekly" => Ok(Frequency::Weekly),
+            "monthly" => Ok(Frequency::Monthly),
+            "yearly" => Ok(Frequency::Yearly),
+            _ => Err(format!("Invalid frequency: {}", s)),
+        }
+    }
+}
+
+impl Frequency {
+    /// The first occurrence strictly after `after` for a rule anchored at `start`.
+    /// Monthly and yearly rules keep the anchor's day of month, clamped to the
+    /// end of shorter months (a rule starting on Jan 31 runs on Feb 28, then Mar 31).
+    pub fn next_occurrence(&self, start: NaiveDate, after: NaiveDate) -> NaiveDate {
+        if after < start {
+            return start;
+        }
+        let step = match self {
+            Frequency::Daily => return after + Duration::days(1),
+            Frequency::Weekly => {
+                let weeks = (after - start).num_days() / 7 + 1;
+                return start + Duration::weeks(weeks);
+            }
+            Frequency::Monthly => 1,
+            Frequency::Yearly => 12,
+        };
+        let months = (after.year() - start.year()) * 12 + after.month() as i32 - start.month() as i32;
+        let mut n = (months / step).max(0) as u32;
+        loop {
+            let date = start + Months::new(n * step as u32);
+            if date > after {
+                return date;
+            }
+            n += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RecurringExpense {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub category: String,
+    pub note: Option<String>,
+    pub frequency: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub next_run_date: NaiveDate,
+    pub last_run_date: Option<NaiveDate>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateRecurringExpense {
+    pub amount: Decimal,
+    /// Defaults to the user's base currency.
+    pub currency: Option<String>,
+    pub category: String,
+    pub note: Option<String>,
+    pub frequency: Frequency,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateRecurringExpense {
+    pub amount: Option<Decimal>,
+    pub category: Option<String>,
+    /// `Some(None)` when sent as `null`, to clear the note.
+    #[serde(default, deserialize_with = "present")]
+    pub note: Option<Option<String>>,
+    /// `Some(None)` when sent as `null`, so the rule runs indefinitely.
+    #[serde(default, deserialize_with = "present")]
+    pub end_date: Option<Option<NaiveDate>>,
+    pub active: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    #[test]
+    fn before_start_is_start() {
+        let start = d(2026, 3, 10);
+        for frequency in [Frequency::Daily, Frequency::Weekly, Frequency::Monthly, Frequency::Yearly] {
+            assert_eq!(frequency.next_occurrence(start, d(2026, 3, 1)), start);
+        }
+    }
+
+    #[test]
+    fn daily_and_weekly() {
+        let start = d(2026, 3, 10);
+        assert_eq!(Frequency::Daily.next_occurrence(start, start), d(2026, 3, 11));
+        assert_eq!(Frequency::Weekly.next_occurrence(start, start), d(2026, 3, 17));
+        assert_eq!(Frequency::Weekly.next_occurrence(start, d(2026, 3, 16)), d(2026, 3, 17));
+        assert_eq!(Frequency::Weekly.next_occurrence(start, d(2026, 3, 17)), d(2026, 3, 24));
+    }
+
+    #[test]
+    fn monthly_clamps_to_month_end() {
+        let start = d(2026, 1, 31);
+        assert_eq!(Frequency::Monthly.next_occurrence(start, start), d(2026, 2, 28));
+        assert_eq!(Frequency::Monthly.next_occurrence(start, d(2026, 2, 28)), d(2026, 3, 31));
+        assert_eq!(Frequency::Monthly.next_occurrence(start, d(2026, 4, 15)), d(2026, 4, 30));
+        // Leap years keep the 29th
+        assert_eq!(Frequency::Monthly.next_occurrence(start, d(2028, 1, 31)), d(2028, 2, 29));
+    }
+
+    #[test]
+    fn monthly_skips_earlier_day_in_same_month() {
+        let start = d(2026, 1, 15);
+        assert_eq!(Frequency::Monthly.next_occurrence(start, d(2026, 5, 20)), d(2026, 6, 15));
+        assert_eq!(Frequency::Monthly.next_occurrence(start, d(2026, 5, 14)), d(2026, 5, 15));
+    }
+
+    #[test]
+    fn yearly_from_leap_day() {
+        let start = d(2024, 2, 29);
+        assert_eq!(Frequency::Yearly.next_occurrence(start, start), d(2025, 2, 28));
+        assert_eq!(Frequency::Yearly.next_occurrence(start, d(2027, 3, 1)), d(2028, 2, 29));
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Sends tried for one day's reminder before giving up.
+pub const MAX_ATTEMPTS: i32 = 5;
+
+/// Wait after the first failed send; doubles after each further failure.
+pub const RETRY_AFTER_MINUTES: i32 = 15;
+
+/// A claim still pending after this long is from a run that crashed.
+pub const STALE_CLAIM_MINUTES: i32 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DailyReminder {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub reminder_date: NaiveDate,
+    pub status: String,
+    pub error: Option<String>,
+    pub attempts: i32,
+    pub claimed_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
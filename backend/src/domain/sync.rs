@@ -0,0 +1,152 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use uuid::Uuid;
+
+use crate::domain::expense::Expense;
+use crate::domain::meeting_note::MeetingNote;
+
+/// Most mutations accepted in one sync request.
+pub const MAX_MUTATIONS: usize = 500;
+/// Most changed rows returned by one sync response.
+pub const MAX_CHANGES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncEntity {
+    Expense,
+    MeetingNote,
+}
+
+impl SyncEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncEntity::Expense => "expense",
+            SyncEntity::MeetingNote => "meeting_note",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncRequest {
+    /// `sync_token` from the previous response; omit on the first sync.
+    pub sync_token: Option<String>,
+    #[serde(default)]
+    pub expenses: Vec<ExpenseMutation>,
+    #[serde(default)]
+    pub meeting_notes: Vec<MeetingNoteMutation>,
+}
+
+/// The full state of an expense as last edited on the client, or its
+/// deletion. `id` is generated by the client when creating.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpenseMutation {
+    pub id: Uuid,
+    /// When the client made the edit; the later edit wins a conflict.
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub deleted: bool,
+    pub amount: Option<Decimal>,
+    /// Defaults to the user's base currency.
+    pub currency: Option<String>,
+    pub category: Option<String>,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub expense_date: Option<NaiveDate>,
+}
+
+/// A meeting note's title and duration as edited offline, or its deletion.
+/// Audio is uploaded separately once the note exists on the server.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MeetingNoteMutation {
+    pub id: Uuid,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub deleted: bool,
+    pub meeting_title: Option<String>,
+    pub duration_secs: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MutationStatus {
+    /// Saved, or already saved by an earlier attempt.
+    Applied,
+    /// The server has a later edit or deletion, which is kept.
+    Conflict,
+    /// Invalid; `error` says why.
+    Rejected,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MutationResult {
+    pub entity: SyncEntity,
+    pub id: Uuid,
+    pub status: MutationStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Tombstone {
+    pub entity: String,
+    pub entity_id: Uuid,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Who owns a row and when it was last edited or trashed, to check a synced
+/// mutation against.
+#[derive(Debug, Clone, Copy, sqlx::FromRow)]
+pub struct SyncState {
+    pub user_id: Uuid,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// A synced row with the version of its last write.
+#[derive(Debug)]
+pub struct Versioned<T> {
+    pub row: T,
+    pub row_version: i64,
+}
+
+impl<'r, T: FromRow<'r, PgRow>> FromRow<'r, PgRow> for Versioned<T> {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Versioned { row: T::from_row(row)?, row_version: row.try_get("row_version")? })
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncChanges {
+    pub expenses: Vec<Expense>,
+    pub meeting_notes: Vec<MeetingNote>,
+    pub deleted: Vec<Tombstone>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncResponse {
+    /// One per mutation, in request order.
+    pub results: Vec<MutationResult>,
+    /// Rows written or deleted since the request's token, including the
+    /// client's own applied mutations. A row may be sent more than once.
+    pub changes: SyncChanges,
+    pub sync_token: String,
+    /// More changes are waiting; sync again with the new token.
+    pub has_more: bool,
+}
+
+/// Where a client is in the change feed. Rows are returned in version
+/// order; `since` is a transaction id floor so that rows written by
+/// transactions still running at the last pull are picked up once they
+/// commit, even though their versions are older.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncToken {
+    /// Only rows written by transactions from this id on.
+    pub since: i64,
+    /// Only rows with a later version, while paging through a pull.
+    pub after: i64,
+    /// `since` for the pull after this one, fixed when paging starts.
+    pub next: i64,
+}
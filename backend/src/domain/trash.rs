@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+
+use crate::domain::expense::Expense;
+use crate::domain::meeting_note::MeetingNote;
+
+/// A deleted row, kept until the trash is purged.
+#[derive(Debug, Serialize)]
+pub struct Trashed<T> {
+    #[serde(flatten)]
+    pub item: T,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl<'r, T: FromRow<'r, PgRow>> FromRow<'r, PgRow> for Trashed<T> {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Trashed { item: T::from_row(row)?, deleted_at: row.try_get("deleted_at")? })
+    }
+}
+
+/// Everything in a user's trash, most recently deleted first.
+#[derive(Debug, Serialize)]
+pub struct Trash {
+    pub expenses: Vec<Trashed<Expense>>,
+    pub meeting_notes: Vec<Trashed<MeetingNote>>,
+    /// Days after deletion that items are purged for good.
+    pub retention_days: i32,
+}
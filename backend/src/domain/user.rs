@@ -1,4 +1,5 @@
-use chrono::{DateTime, NaiveTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -9,12 +10,37 @@ pub struct User {
     pub email: String,
     pub name: String,
     pub notification_time: NaiveTime,
+    pub timezone: String,
+    /// Currency that summaries and budgets are reported in.
+    pub base_currency: String,
+    /// Push an alert when a new expense is flagged as unusually large.
+    pub anomaly_alerts: bool,
     pub sns_endpoint_arn: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+impl User {
+    /// The user's IANA timezone, falling back to UTC if the stored value is unknown.
+    pub fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
+
+    /// The given instant expressed in the user's local time.
+    pub fn local_time(&self, at: DateTime<Utc>) -> DateTime<Tz> {
+        at.with_timezone(&self.tz())
+    }
+
+    /// The current calendar date in the user's timezone.
+    pub fn today(&self) -> NaiveDate {
+        self.local_time(Utc::now()).date_naive()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateUserProfile {
     pub name: Option<String>,
     pub notification_time: Option<NaiveTime>,
+    pub timezone: Option<String>,
+    pub base_currency: Option<String>,
+    pub anomaly_alerts: Option<bool>,
 }
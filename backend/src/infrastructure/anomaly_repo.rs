@@ -0,0 +1,66 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::anomaly::*;
+use crate::domain::expense::Expense;
+use crate::errors::AppError;
+
+pub async fn create(
+    pool: &PgPool,
+    expense: &Expense,
+    flag: &AnomalyFlag,
+) -> Result<ExpenseAnomaly, AppError> {
+    let row = sqlx::query_as::<_, ExpenseAnomaly>(
+        r#"INSERT INTO expense_anomalies
+               (expense_id, user_id, category, amount, rule, reason, mean, std_dev, p95, sample_size, expense_date)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+           RETURNING id, expense_id, user_id, category, amount, rule, reason, mean, std_dev, p95,
+                     sample_size, expense_date, created_at"#,
+    )
+    .bind(expense.id)
+    .bind(expense.user_id)
+    .bind(&expense.category)
+    .bind(flag.amount)
+    .bind(flag.rule.to_string())
+    .bind(&flag.reason)
+    .bind(flag.typical_amount)
+    .bind(flag.std_dev)
+    .bind(flag.p95)
+    .bind(flag.sample_size as i32)
+    .bind(expense.expense_date)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_all(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: &AnomalyFilter,
+) -> Result<Vec<ExpenseAnomaly>, AppError> {
+    let page = filter.page.unwrap_or(1).max(1);
+    let per_page = filter.per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    let rows = sqlx::query_as::<_, ExpenseAnomaly>(
+        r#"SELECT id, expense_id, user_id, category, amount, rule, reason, mean, std_dev, p95,
+                  sample_size, expense_date, created_at
+           FROM expense_anomalies a
+           WHERE user_id = $1
+             AND NOT EXISTS (SELECT 1 FROM expenses e WHERE e.id = a.expense_id AND e.deleted_at IS NOT NULL)
+             AND ($2::date IS NULL OR expense_date >= $2)
+             AND ($3::date IS NULL OR expense_date <= $3)
+             AND ($4::text IS NULL OR category = $4)
+           ORDER BY expense_date DESC, created_at DESC
+           LIMIT $5 OFFSET $6"#,
+    )
+    .bind(user_id)
+    .bind(filter.start_date)
+    .bind(filter.end_date)
+    .bind(&filter.category)
+    .bind(per_page)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
@@ -0,0 +1,67 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::attachment::ExpenseAttachment;
+use crate::errors::AppError;
+
+pub async fn create(
+    pool: &PgPool,
+    expense_id: Uuid,
+    user_id: Uuid,
+    s3_key: &str,
+    file_name: &str,
+    content_type: &str,
+    size_bytes: i64,
+) -> Result<ExpenseAttachment, AppError> {
+    let row = sqlx::query_as::<_, ExpenseAttachment>(
+        r#"INSERT INTO expense_attachments (expense_id, user_id, s3_key, file_name, content_type, size_bytes)
+           VALUES ($1, $2, $3, $4, $5, $6)
+           RETURNING id, expense_id, user_id, s3_key, file_name, content_type, size_bytes, created_at"#,
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .bind(s3_key)
+    .bind(file_name)
+    .bind(content_type)
+    .bind(size_bytes)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_by_expense(
+    pool: &PgPool,
+    expense_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<ExpenseAttachment>, AppError> {
+    let rows = sqlx::query_as::<_, ExpenseAttachment>(
+        r#"SELECT id, expense_id, user_id, s3_key, file_name, content_type, size_bytes, created_at
+           FROM expense_attachments
+           WHERE expense_id = $1 AND user_id = $2
+           ORDER BY created_at ASC"#,
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn delete(
+    pool: &PgPool,
+    id: Uuid,
+    expense_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<String>, AppError> {
+    let row = sqlx::query_scalar::<_, String>(
+        r#"DELETE FROM expense_attachments
+           WHERE id = $1 AND expense_id = $2 AND user_id = $3
+           RETURNING s3_key"#,
+    )
+    .bind(id)
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
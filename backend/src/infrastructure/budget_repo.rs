@@ -1,69 +1,158 @@
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::domain::budget::BudgetSetting;
+use crate::domain::budget::{BudgetSetting, CategoryBudget, SetBudgetRequest};
 use crate::errors::AppError;
 
-pub async fn get(pool: &PgPool, user_id: Uuid) -> Result<Option<BudgetSetting>, AppError> {
-    let row = sqlx::query_as::<_, BudgetSetting>(
-        r#"SELECT id, user_id, daily_budget, created_at, updated_at
-           FROM user_budget_settings WHERE user_id = $1"#,
+/// Every budget setting the user has had, oldest first.
+pub async fn history(pool: &PgPool, user_id: Uuid) -> Result<Vec<BudgetSetting>, AppError> {
+    let rows = sqlx::query_as::<_, BudgetSetting>(
+        r#"SELECT id, user_id, amount, period, carry_over, carry_over_cap, carry_over_periods,
+                  effective_from, created_at, updated_at
+           FROM user_budget_settings WHERE user_id = $1
+           ORDER BY effective_from"#,
     )
     .bind(user_id)
-    .fetch_optional(pool)
+    .fetch_all(pool)
     .await?;
-    Ok(row)
+    Ok(rows)
 }
 
+/// Record a budget setting taking effect on `effective_from`. A second change
+/// on the same day replaces the first.
 pub async fn upsert(
     pool: &PgPool,
     user_id: Uuid,
-    daily_budget: Decimal,
+    input: &SetBudgetRequest,
+    effective_from: NaiveDate,
 ) -> Result<BudgetSetting, AppError> {
     let row = sqlx::query_as::<_, BudgetSetting>(
-        r#"INSERT INTO user_budget_settings (user_id, daily_budget)
-           VALUES ($1, $2)
-           ON CONFLICT (user_id)
-           DO UPDATE SET daily_budget = $2, updated_at = NOW()
-           RETURNING id, user_id, daily_budget, created_at, updated_at"#,
+        r#"INSERT INTO user_budget_settings
+               (user_id, amount, period, carry_over, carry_over_cap, carry_over_periods, effective_from)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)
+           ON CONFLICT (user_id, effective_from)
+           DO UPDATE SET amount = $2, period = $3, carry_over = $4,
+                         carry_over_cap = $5, carry_over_periods = $6, updated_at = NOW()
+           RETURNING id, user_id, amount, period, carry_over, carry_over_cap, carry_over_periods,
+                     effective_from, created_at, updated_at"#,
     )
     .bind(user_id)
-    .bind(daily_budget)
+    .bind(input.amount)
+    .bind(input.period.to_string())
+    .bind(input.carry_over.to_string())
+    .bind(input.carry_over_cap)
+    .bind(input.carry_over_periods)
+    .bind(effective_from)
     .fetch_one(pool)
     .await?;
     Ok(row)
 }
 
-/// Get total spent on a specific date
-pub async fn spent_on_date(
+/// Get daily spending for a date range (for carryover calculation)
+pub async fn daily_spending(
     pool: &PgPool,
     user_id: Uuid,
-    date: &str,
-) -> Result<Decimal, AppError> {
-    let row: (Decimal,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(amount), 0) FROM expenses WHERE user_id = $1 AND expense_date = $2",
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<(String, Decimal)>, AppError> {
+    let rows: Vec<(String, Decimal)> = sqlx::query_as(
+        r#"SELECT e.expense_date::text,
+                  COALESCE(ROUND(SUM(e.amount * exchange_rate(e.currency, u.base_currency, e.expense_date)), 2), 0)
+           FROM expenses e JOIN users u ON u.id = e.user_id
+           WHERE e.user_id = $1 AND e.deleted_at IS NULL AND e.expense_date >= $2::date AND e.expense_date <= $3::date
+           GROUP BY e.expense_date
+           ORDER BY e.expense_date"#,
     )
     .bind(user_id)
-    .bind(date)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+// Per-category monthly budgets
+
+pub async fn list_category_budgets(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<CategoryBudget>, AppError> {
+    let rows = sqlx::query_as::<_, CategoryBudget>(
+        r#"SELECT id, user_id, category, monthly_limit, created_at, updated_at
+           FROM category_budgets WHERE user_id = $1
+           ORDER BY category"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn get_category_budget(
+    pool: &PgPool,
+    user_id: Uuid,
+    category: &str,
+) -> Result<Option<CategoryBudget>, AppError> {
+    let row = sqlx::query_as::<_, CategoryBudget>(
+        r#"SELECT id, user_id, category, monthly_limit, created_at, updated_at
+           FROM category_budgets WHERE user_id = $1 AND category = $2"#,
+    )
+    .bind(user_id)
+    .bind(category)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn upsert_category_budget(
+    pool: &PgPool,
+    user_id: Uuid,
+    category: &str,
+    monthly_limit: Decimal,
+) -> Result<CategoryBudget, AppError> {
+    let row = sqlx::query_as::<_, CategoryBudget>(
+        r#"INSERT INTO category_budgets (user_id, category, monthly_limit)
+           VALUES ($1, $2, $3)
+           ON CONFLICT (user_id, category)
+           DO UPDATE SET monthly_limit = $3, updated_at = NOW()
+           RETURNING id, user_id, category, monthly_limit, created_at, updated_at"#,
+    )
+    .bind(user_id)
+    .bind(category)
+    .bind(monthly_limit)
     .fetch_one(pool)
     .await?;
-    Ok(row.0)
+    Ok(row)
 }
 
-/// Get daily spending for a date range (for carryover calculation)
-pub async fn daily_spending(
+pub async fn delete_category_budget(
     pool: &PgPool,
     user_id: Uuid,
-    start_date: &str,
-    end_date: &str,
+    category: &str,
+) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM category_budgets WHERE user_id = $1 AND category = $2")
+        .bind(user_id)
+        .bind(category)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Total spent per category in a date range
+pub async fn category_spending(
+    pool: &PgPool,
+    user_id: Uuid,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
 ) -> Result<Vec<(String, Decimal)>, AppError> {
     let rows: Vec<(String, Decimal)> = sqlx::query_as(
-        r#"SELECT expense_date::text, COALESCE(SUM(amount), 0)
-           FROM expenses
-           WHERE user_id = $1 AND expense_date >= $2 AND expense_date <= $3
-           GROUP BY expense_date
-           ORDER BY expense_date"#,
+        r#"SELECT e.category,
+                  COALESCE(ROUND(SUM(e.amount * exchange_rate(e.currency, u.base_currency, e.expense_date)), 2), 0)
+           FROM expenses e JOIN users u ON u.id = e.user_id
+           WHERE e.user_id = $1 AND e.deleted_at IS NULL AND e.expense_date >= $2 AND e.expense_date <= $3
+           GROUP BY e.category"#,
     )
     .bind(user_id)
     .bind(start_date)
@@ -72,3 +161,26 @@ pub async fn daily_spending(
     .await?;
     Ok(rows)
 }
+
+/// Record that a category crossed `threshold` percent of its limit in `month`.
+/// Returns false if that alert was already recorded.
+pub async fn record_category_alert(
+    pool: &PgPool,
+    user_id: Uuid,
+    category: &str,
+    month: NaiveDate,
+    threshold: i32,
+) -> Result<bool, AppError> {
+    let result = sqlx::query(
+        r#"INSERT INTO category_budget_alerts (user_id, category, month, threshold)
+           VALUES ($1, $2, $3, $4)
+           ON CONFLICT (user_id, category, month, threshold) DO NOTHING"#,
+    )
+    .bind(user_id)
+    .bind(category)
+    .bind(month)
+    .bind(threshold)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
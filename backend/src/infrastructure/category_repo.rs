@@ -0,0 +1,179 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::category::*;
+use crate::errors::AppError;
+
+pub async fn seed_defaults(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+    for (name, icon, color) in DEFAULT_CATEGORIES {
+        sqlx::query(
+            r#"INSERT INTO categories (user_id, name, icon, color)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (user_id, name) DO NOTHING"#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(icon)
+        .bind(color)
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+pub async fn find_all(pool: &PgPool, user_id: Uuid) -> Result<Vec<Category>, AppError> {
+    let rows = sqlx::query_as::<_, Category>(
+        r#"SELECT id, user_id, name, icon, color, created_at, updated_at
+           FROM categories WHERE user_id = $1
+           ORDER BY name"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn find_by_id(
+    pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<Category>, AppError> {
+    let row = sqlx::query_as::<_, Category>(
+        r#"SELECT id, user_id, name, icon, color, created_at, updated_at
+           FROM categories WHERE id = $1 AND user_id = $2"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn exists(pool: &PgPool, user_id: Uuid, name: &str) -> Result<bool, AppError> {
+    let row = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM categories WHERE user_id = $1 AND name = $2)",
+    )
+    .bind(user_id)
+    .bind(name)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn create(
+    pool: &PgPool,
+    user_id: Uuid,
+    name: &str,
+    category: &CreateCategory,
+) -> Result<Option<Category>, AppError> {
+    let row = sqlx::query_as::<_, Category>(
+        r#"INSERT INTO categories (user_id, name, icon, color)
+           VALUES ($1, $2, $3, $4)
+           ON CONFLICT (user_id, name) DO NOTHING
+           RETURNING id, user_id, name, icon, color, created_at, updated_at"#,
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(&category.icon)
+    .bind(&category.color)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+/// Update a category. A rename is applied to the user's existing expenses and
+/// category budget in the same transaction so they stay grouped under the new name.
+pub async fn update(
+    pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+    name: Option<&str>,
+    category: &UpdateCategory,
+) -> Result<Option<Category>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let old_name = sqlx::query_scalar::<_, String>(
+        "SELECT name FROM categories WHERE id = $1 AND user_id = $2 FOR UPDATE",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(old_name) = old_name else {
+        return Ok(None);
+    };
+
+    let row = sqlx::query_as::<_, Category>(
+        r#"UPDATE categories
+           SET name = COALESCE($3, name),
+               icon = CASE WHEN $4 THEN $5 ELSE icon END,
+               color = CASE WHEN $6 THEN $7 ELSE color END,
+               updated_at = NOW()
+           WHERE id = $1 AND user_id = $2
+           RETURNING id, user_id, name, icon, color, created_at, updated_at"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(name)
+    .bind(category.icon.is_some())
+    .bind(category.icon.as_ref().and_then(Option::as_deref))
+    .bind(category.color.is_some())
+    .bind(category.color.as_ref().and_then(Option::as_deref))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if row.name != old_name {
+        sqlx::query("UPDATE expenses SET category = $3, updated_at = NOW() WHERE user_id = $1 AND category = $2")
+            .bind(user_id)
+            .bind(&old_name)
+            .bind(&row.name)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE category_budgets SET category = $3, updated_at = NOW() WHERE user_id = $1 AND category = $2")
+            .bind(user_id)
+            .bind(&old_name)
+            .bind(&row.name)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(Some(row))
+}
+
+/// Delete a category together with its monthly budget, if any.
+pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let name = sqlx::query_scalar::<_, String>(
+        "DELETE FROM categories WHERE id = $1 AND user_id = $2 RETURNING name",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(name) = name else {
+        return Ok(false);
+    };
+
+    sqlx::query("DELETE FROM category_budgets WHERE user_id = $1 AND category = $2")
+        .bind(user_id)
+        .bind(&name)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Expenses in the category, counting trashed ones since they may be restored.
+pub async fn expense_count(pool: &PgPool, user_id: Uuid, name: &str) -> Result<i64, AppError> {
+    let row = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM expenses WHERE user_id = $1 AND category = $2",
+    )
+    .bind(user_id)
+    .bind(name)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
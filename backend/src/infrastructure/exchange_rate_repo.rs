@@ -0,0 +1,73 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+
+/// The first date from which amounts in `from` can be converted to `to`,
+/// i.e. the earliest rate between the two in either direction.
+pub async fn first_rate_date(
+    pool: &PgPool,
+    from: &str,
+    to: &str,
+) -> Result<Option<NaiveDate>, AppError> {
+    let row = sqlx::query_scalar::<_, Option<NaiveDate>>(
+        r#"SELECT MIN(rate_date) FROM exchange_rates
+           WHERE (from_currency = $1 AND to_currency = $2)
+              OR (from_currency = $2 AND to_currency = $1)"#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
+/// Convert `amount` using the rate in effect on `date`. Returns `None` if no
+/// rate is available.
+pub async fn convert(
+    pool: &PgPool,
+    amount: Decimal,
+    from: &str,
+    to: &str,
+    date: NaiveDate,
+) -> Result<Option<Decimal>, AppError> {
+    let row = sqlx::query_scalar::<_, Option<Decimal>>(
+        "SELECT ROUND($1 * exchange_rate($2, $3, $4), 2)",
+    )
+    .bind(amount)
+    .bind(from)
+    .bind(to)
+    .bind(date)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
+/// Each non-`base` currency the user has expenses, recurring expenses or
+/// incomes in,
+/// with the earliest date it is used on.
+pub async fn currencies_in_use(
+    pool: &PgPool,
+    user_id: Uuid,
+    base: &str,
+) -> Result<Vec<(String, NaiveDate)>, AppError> {
+    let rows: Vec<(String, NaiveDate)> = sqlx::query_as(
+        r#"SELECT currency, MIN(date) FROM (
+               SELECT currency, expense_date AS date FROM expenses WHERE user_id = $1 AND deleted_at IS NULL
+               UNION ALL
+               SELECT currency, start_date AS date FROM recurring_expenses WHERE user_id = $1 AND active
+               UNION ALL
+               SELECT currency, income_date AS date FROM incomes WHERE user_id = $1
+           ) used
+           WHERE currency <> $2
+           GROUP BY currency
+           ORDER BY currency"#,
+    )
+    .bind(user_id)
+    .bind(base)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
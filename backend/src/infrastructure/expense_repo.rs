@@ -1,35 +1,44 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
-use sqlx::PgPool;
+use sqlx::postgres::PgArguments;
+use sqlx::query::QueryAs;
+use sqlx::{PgExecutor, PgPool, Postgres};
 use uuid::Uuid;
 
+use crate::domain::analytics::{Bucket, TimeseriesRow};
+use crate::domain::anomaly::AmountStats;
 use crate::domain::expense::*;
+use crate::domain::sync::SyncState;
+use crate::domain::trash::Trashed;
 use crate::errors::AppError;
 
-pub async fn create(
-    pool: &PgPool,
+pub async fn create<'e, E: PgExecutor<'e>>(
+    executor: E,
     user_id: Uuid,
     expense: &CreateExpense,
+    currency: &str,
+    expense_date: NaiveDate,
 ) -> Result<Expense, AppError> {
-    let expense_date = expense.expense_date.unwrap_or_else(|| chrono::Utc::now().date_naive());
     let row = sqlx::query_as::<_, Expense>(
-        r#"INSERT INTO expenses (user_id, amount, category, note, expense_date)
-           VALUES ($1, $2, $3, $4, $5)
-           RETURNING id, user_id, amount, category, note, expense_date, created_at, updated_at"#,
+        r#"INSERT INTO expenses (user_id, amount, currency, category, note, tags, expense_date)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)
+           RETURNING id, user_id, amount, currency, category, note, tags, expense_date, created_at, updated_at"#,
     )
     .bind(user_id)
     .bind(expense.amount)
-    .bind(expense.category.to_string())
+    .bind(currency)
+    .bind(&expense.category)
     .bind(&expense.note)
+    .bind(&expense.tags)
     .bind(expense_date)
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await?;
     Ok(row)
 }
 
 pub async fn find_by_id(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<Option<Expense>, AppError> {
     let row = sqlx::query_as::<_, Expense>(
-        "SELECT id, user_id, amount, category, note, expense_date, created_at, updated_at FROM expenses WHERE id = $1 AND user_id = $2"
+        "SELECT id, user_id, amount, currency, category, note, tags, expense_date, created_at, updated_at FROM expenses WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"
     )
     .bind(id)
     .bind(user_id)
@@ -38,36 +47,165 @@ pub async fn find_by_id(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<Option
     Ok(row)
 }
 
+/// Fetch up to `limit` filtered expenses, newest first in descending
+/// `(expense_date, created_at, id)` order, starting after `before`.
 pub async fn find_all(
     pool: &PgPool,
     user_id: Uuid,
     filter: &ExpenseFilter,
+    before: Option<ExpenseKey>,
+    limit: i64,
 ) -> Result<Vec<Expense>, AppError> {
-    let page = filter.page.unwrap_or(1).max(1);
-    let per_page = filter.per_page.unwrap_or(20).clamp(1, 100);
-    let offset = (page - 1) * per_page;
+    let (before_date, before_created, before_id) = match before {
+        Some((date, created_at, id)) => (Some(date), Some(created_at), Some(id)),
+        None => (None, None, None),
+    };
+    let rows = sqlx::query_as::<_, Expense>(
+        r#"SELECT id, user_id, amount, currency, category, note, tags, expense_date, created_at, updated_at
+           FROM expenses
+           WHERE user_id = $1 AND deleted_at IS NULL
+             AND ($2::date IS NULL OR expense_date >= $2)
+             AND ($3::date IS NULL OR expense_date <= $3)
+             AND ($4::text IS NULL OR category = $4)
+             AND ($5::date IS NULL OR (expense_date, created_at, id) < ($5, $6, $7))
+           ORDER BY expense_date DESC, created_at DESC, id DESC
+           LIMIT $8"#,
+    )
+    .bind(user_id)
+    .bind(filter.start_date)
+    .bind(filter.end_date)
+    .bind(&filter.category)
+    .bind(before_date)
+    .bind(before_created)
+    .bind(before_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
 
+pub async fn count(pool: &PgPool, user_id: Uuid, filter: &ExpenseFilter) -> Result<i64, AppError> {
+    let row: (i64,) = sqlx::query_as(
+        r#"SELECT COUNT(*)
+           FROM expenses
+           WHERE user_id = $1 AND deleted_at IS NULL
+             AND ($2::date IS NULL OR expense_date >= $2)
+             AND ($3::date IS NULL OR expense_date <= $3)
+             AND ($4::text IS NULL OR category = $4)"#,
+    )
+    .bind(user_id)
+    .bind(filter.start_date)
+    .bind(filter.end_date)
+    .bind(&filter.category)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.0)
+}
+
+/// Fetch up to `limit` filtered expenses in ascending
+/// `(expense_date, created_at, id)` order, starting after `after`.
+pub async fn find_batch(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: &ExpenseFilter,
+    after: Option<ExpenseKey>,
+    limit: i64,
+) -> Result<Vec<Expense>, AppError> {
+    let (after_date, after_created, after_id) = match after {
+        Some((date, created_at, id)) => (Some(date), Some(created_at), Some(id)),
+        None => (None, None, None),
+    };
     let rows = sqlx::query_as::<_, Expense>(
-        r#"SELECT id, user_id, amount, category, note, expense_date, created_at, updated_at
+        r#"SELECT id, user_id, amount, currency, category, note, tags, expense_date, created_at, updated_at
            FROM expenses
-           WHERE user_id = $1
+           WHERE user_id = $1 AND deleted_at IS NULL
              AND ($2::date IS NULL OR expense_date >= $2)
              AND ($3::date IS NULL OR expense_date <= $3)
              AND ($4::text IS NULL OR category = $4)
-           ORDER BY expense_date DESC, created_at DESC
-           LIMIT $5 OFFSET $6"#,
+             AND ($5::date IS NULL OR (expense_date, created_at, id) > ($5, $6, $7))
+           ORDER BY expense_date, created_at, id
+           LIMIT $8"#,
     )
     .bind(user_id)
     .bind(filter.start_date)
     .bind(filter.end_date)
-    .bind(filter.category.map(|c| c.to_string()))
-    .bind(per_page)
-    .bind(offset)
+    .bind(&filter.category)
+    .bind(after_date)
+    .bind(after_created)
+    .bind(after_id)
+    .bind(limit)
     .fetch_all(pool)
     .await?;
     Ok(rows)
 }
 
+/// Conditions for `search` and `search_count`, with the criteria bound by
+/// `bind_search` as $1 to $8. Amount bounds are in the user's base currency.
+const SEARCH_CONDITIONS: &str = r#"user_id = $1 AND deleted_at IS NULL
+    AND ($2::text IS NULL OR expense_search_vector(note, tags) @@ websearch_to_tsquery('english', $2))
+    AND ($3::text[] IS NULL OR tags @> $3)
+    AND ($4::text[] IS NULL OR category = ANY($4))
+    AND ($5::numeric IS NULL AND $6::numeric IS NULL
+         OR (SELECT ROUND(amount * exchange_rate(currency, u.base_currency, expense_date), 2)
+             FROM users u WHERE u.id = $1) BETWEEN COALESCE($5, '-Infinity') AND COALESCE($6, 'Infinity'))
+    AND ($7::date IS NULL OR expense_date >= $7)
+    AND ($8::date IS NULL OR expense_date <= $8)"#;
+
+fn bind_search<'q, O>(
+    query: QueryAs<'q, Postgres, O, PgArguments>,
+    user_id: Uuid,
+    criteria: &'q ExpenseSearchCriteria,
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    query
+        .bind(user_id)
+        .bind(&criteria.q)
+        .bind(&criteria.tags)
+        .bind(&criteria.categories)
+        .bind(criteria.min_amount)
+        .bind(criteria.max_amount)
+        .bind(criteria.start_date)
+        .bind(criteria.end_date)
+}
+
+/// Expenses matching `criteria`, best text matches first and then newest first.
+pub async fn search(
+    pool: &PgPool,
+    user_id: Uuid,
+    criteria: &ExpenseSearchCriteria,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Expense>, AppError> {
+    let sql = format!(
+        r#"SELECT id, user_id, amount, currency, category, note, tags, expense_date, created_at, updated_at
+           FROM expenses
+           WHERE {}
+           ORDER BY CASE WHEN $2::text IS NULL THEN 0
+                         ELSE ts_rank(expense_search_vector(note, tags), websearch_to_tsquery('english', $2))
+                    END DESC,
+                    expense_date DESC, created_at DESC, id DESC
+           LIMIT $9 OFFSET $10"#,
+        SEARCH_CONDITIONS
+    );
+    let rows = bind_search(sqlx::query_as::<_, Expense>(&sql), user_id, criteria)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+pub async fn search_count(
+    pool: &PgPool,
+    user_id: Uuid,
+    criteria: &ExpenseSearchCriteria,
+) -> Result<i64, AppError> {
+    let sql = format!("SELECT COUNT(*) FROM expenses WHERE {}", SEARCH_CONDITIONS);
+    let row: (i64,) = bind_search(sqlx::query_as(&sql), user_id, criteria)
+        .fetch_one(pool)
+        .await?;
+    Ok(row.0)
+}
+
 pub async fn update(
     pool: &PgPool,
     id: Uuid,
@@ -80,31 +218,173 @@ pub async fn update(
                category = COALESCE($4, category),
                note = COALESCE($5, note),
                expense_date = COALESCE($6, expense_date),
+               currency = COALESCE($7, currency),
+               tags = COALESCE($8, tags),
                updated_at = NOW()
-           WHERE id = $1 AND user_id = $2
-           RETURNING id, user_id, amount, category, note, expense_date, created_at, updated_at"#,
+           WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+           RETURNING id, user_id, amount, currency, category, note, tags, expense_date, created_at, updated_at"#,
     )
     .bind(id)
     .bind(user_id)
     .bind(expense.amount)
-    .bind(expense.category.map(|c| c.to_string()))
+    .bind(&expense.category)
     .bind(&expense.note)
     .bind(expense.expense_date)
+    .bind(&expense.currency)
+    .bind(&expense.tags)
     .fetch_optional(pool)
     .await?;
     Ok(row)
 }
 
-pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
-    let result = sqlx::query("DELETE FROM expenses WHERE id = $1 AND user_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .execute(pool)
-        .await?;
+/// Move an expense to the trash, leaving a tombstone for offline clients
+/// to sync.
+pub async fn delete<'e, E: PgExecutor<'e>>(executor: E, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+    let result = sqlx::query(
+        r#"WITH deleted AS (
+               UPDATE expenses SET deleted_at = NOW()
+               WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+               RETURNING id, user_id
+           )
+           INSERT INTO sync_tombstones (entity, entity_id, user_id)
+           SELECT 'expense', id, user_id FROM deleted"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(executor)
+    .await?;
     Ok(result.rows_affected() > 0)
 }
 
-// Analytics queries
+/// Take an expense out of the trash. Restoring counts as an edit, so it
+/// wins over offline edits made before it.
+pub async fn restore<'e, E: PgExecutor<'e>>(executor: E, id: Uuid, user_id: Uuid) -> Result<Option<Expense>, AppError> {
+    let row = sqlx::query_as::<_, Expense>(
+        r#"WITH restored AS (
+               UPDATE expenses SET deleted_at = NULL, updated_at = NOW()
+               WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+               RETURNING id, user_id, amount, currency, category, note, tags, expense_date, created_at, updated_at
+           ),
+           tombstone AS (
+               DELETE FROM sync_tombstones
+               WHERE entity = 'expense' AND entity_id IN (SELECT id FROM restored)
+           )
+           SELECT * FROM restored"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_deleted(pool: &PgPool, user_id: Uuid) -> Result<Vec<Trashed<Expense>>, AppError> {
+    let rows = sqlx::query_as::<_, Trashed<Expense>>(
+        r#"SELECT id, user_id, amount, currency, category, note, tags, expense_date, created_at, updated_at, deleted_at
+           FROM expenses
+           WHERE user_id = $1 AND deleted_at IS NOT NULL
+           ORDER BY deleted_at DESC, id DESC"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Permanently delete expenses trashed more than `retention_days` ago, and
+/// their attachments, returning the attachments' storage keys. Their
+/// tombstones stay for clients that haven't synced since.
+pub async fn purge_deleted<'e, E: PgExecutor<'e>>(executor: E, retention_days: i32) -> Result<Vec<String>, AppError> {
+    let keys = sqlx::query_scalar::<_, String>(
+        r#"WITH purged AS (
+               DELETE FROM expenses WHERE deleted_at < NOW() - make_interval(days => $1)
+               RETURNING id
+           )
+           SELECT a.s3_key FROM expense_attachments a JOIN purged p ON p.id = a.expense_id"#,
+    )
+    .bind(retention_days)
+    .fetch_all(executor)
+    .await?;
+    Ok(keys)
+}
+
+/// Lock an expense by id, trashed or not and whoever owns it, before
+/// applying a synced edit.
+pub async fn find_for_sync<'e, E: PgExecutor<'e>>(executor: E, id: Uuid) -> Result<Option<SyncState>, AppError> {
+    let row = sqlx::query_as::<_, SyncState>(
+        "SELECT user_id, updated_at, deleted_at FROM expenses WHERE id = $1 FOR UPDATE"
+    )
+    .bind(id)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row)
+}
+
+/// Create or overwrite an expense with a client-chosen id, unless the stored
+/// copy was edited at or after `updated_at` or belongs to someone else. A
+/// trashed expense is restored.
+pub async fn upsert<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: Uuid,
+    user_id: Uuid,
+    expense: &CreateExpense,
+    currency: &str,
+    expense_date: NaiveDate,
+    updated_at: DateTime<Utc>,
+) -> Result<Option<Expense>, AppError> {
+    let row = sqlx::query_as::<_, Expense>(
+        r#"INSERT INTO expenses (id, user_id, amount, currency, category, note, tags, expense_date, updated_at)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+           ON CONFLICT (id) DO UPDATE
+           SET amount = EXCLUDED.amount,
+               currency = EXCLUDED.currency,
+               category = EXCLUDED.category,
+               note = EXCLUDED.note,
+               tags = EXCLUDED.tags,
+               expense_date = EXCLUDED.expense_date,
+               updated_at = EXCLUDED.updated_at,
+               deleted_at = NULL
+           WHERE expenses.user_id = EXCLUDED.user_id AND expenses.updated_at < EXCLUDED.updated_at
+           RETURNING id, user_id, amount, currency, category, note, tags, expense_date, created_at, updated_at"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(expense.amount)
+    .bind(currency)
+    .bind(&expense.category)
+    .bind(&expense.note)
+    .bind(&expense.tags)
+    .bind(expense_date)
+    .bind(updated_at)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row)
+}
+
+/// `(expense_date, amount, note)` of every expense in a date range, used to
+/// spot likely duplicates on import.
+pub async fn duplicate_keys(
+    pool: &PgPool,
+    user_id: Uuid,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<(NaiveDate, Decimal, Option<String>)>, AppError> {
+    let rows: Vec<(NaiveDate, Decimal, Option<String>)> = sqlx::query_as(
+        r#"SELECT expense_date, amount, note
+           FROM expenses
+           WHERE user_id = $1 AND deleted_at IS NULL
+             AND expense_date >= $2 AND expense_date <= $3"#,
+    )
+    .bind(user_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+// Analytics queries. Amounts are converted to the user's base currency with
+// the rate in effect on each expense's date.
 
 pub async fn daily_summary(
     pool: &PgPool,
@@ -112,9 +392,12 @@ pub async fn daily_summary(
     date: NaiveDate,
 ) -> Result<ExpenseSummary, AppError> {
     let by_category = sqlx::query_as::<_, CategorySummary>(
-        r#"SELECT category, COALESCE(SUM(amount), 0) as total, COUNT(*) as count
-           FROM expenses WHERE user_id = $1 AND expense_date = $2
-           GROUP BY category ORDER BY total DESC"#,
+        r#"SELECT e.category,
+                  COALESCE(ROUND(SUM(e.amount * exchange_rate(e.currency, u.base_currency, e.expense_date)), 2), 0) as total,
+                  COUNT(*) as count
+           FROM expenses e JOIN users u ON u.id = e.user_id
+           WHERE e.user_id = $1 AND e.deleted_at IS NULL AND e.expense_date = $2
+           GROUP BY e.category ORDER BY total DESC"#,
     )
     .bind(user_id)
     .bind(date)
@@ -134,9 +417,12 @@ pub async fn range_summary(
     end_date: NaiveDate,
 ) -> Result<ExpenseSummary, AppError> {
     let by_category = sqlx::query_as::<_, CategorySummary>(
-        r#"SELECT category, COALESCE(SUM(amount), 0) as total, COUNT(*) as count
-           FROM expenses WHERE user_id = $1 AND expense_date >= $2 AND expense_date <= $3
-           GROUP BY category ORDER BY total DESC"#,
+        r#"SELECT e.category,
+                  COALESCE(ROUND(SUM(e.amount * exchange_rate(e.currency, u.base_currency, e.expense_date)), 2), 0) as total,
+                  COUNT(*) as count
+           FROM expenses e JOIN users u ON u.id = e.user_id
+           WHERE e.user_id = $1 AND e.deleted_at IS NULL AND e.expense_date >= $2 AND e.expense_date <= $3
+           GROUP BY e.category ORDER BY total DESC"#,
     )
     .bind(user_id)
     .bind(start_date)
@@ -150,6 +436,80 @@ pub async fn range_summary(
     Ok(ExpenseSummary { total, count, by_category })
 }
 
+/// Per-bucket, per-category totals for every bucket between `start_date`
+/// and `end_date`, optionally limited to one category. `start_date` should be
+/// the first day of a bucket. Rows are ordered by bucket, then by total
+/// descending.
+pub async fn timeseries(
+    pool: &PgPool,
+    user_id: Uuid,
+    bucket: Bucket,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    category: Option<&str>,
+) -> Result<Vec<TimeseriesRow>, AppError> {
+    let rows = sqlx::query_as::<_, TimeseriesRow>(
+        r#"WITH periods AS (
+               SELECT generate_series(
+                   date_trunc($2, $3::date::timestamp),
+                   $4::date::timestamp,
+                   ('1 ' || $2)::interval
+               )::date AS period_start
+           )
+           SELECT p.period_start, t.category,
+                  COALESCE(t.total, 0) AS total, COALESCE(t.count, 0) AS count
+           FROM periods p
+           LEFT JOIN (
+               SELECT date_trunc($2, e.expense_date::timestamp)::date AS period_start,
+                      e.category,
+                      ROUND(SUM(e.amount * exchange_rate(e.currency, u.base_currency, e.expense_date)), 2) AS total,
+                      COUNT(*) AS count
+               FROM expenses e JOIN users u ON u.id = e.user_id
+               WHERE e.user_id = $1 AND e.deleted_at IS NULL AND e.expense_date >= $3 AND e.expense_date <= $4
+                 AND ($5::text IS NULL OR e.category = $5)
+               GROUP BY 1, 2
+           ) t USING (period_start)
+           ORDER BY p.period_start, t.total DESC"#,
+    )
+    .bind(user_id)
+    .bind(bucket.as_sql())
+    .bind(start_date)
+    .bind(end_date)
+    .bind(category)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Amount statistics for the user's other expenses in `category` dated on
+/// or after `since`, converted to the base currency.
+pub async fn category_amount_stats(
+    pool: &PgPool,
+    user_id: Uuid,
+    category: &str,
+    exclude_id: Uuid,
+    since: NaiveDate,
+) -> Result<AmountStats, AppError> {
+    let row = sqlx::query_as::<_, AmountStats>(
+        r#"SELECT COUNT(amount) AS sample_size,
+                  ROUND(AVG(amount), 2) AS mean,
+                  ROUND(STDDEV_SAMP(amount), 2) AS std_dev,
+                  ROUND((PERCENTILE_CONT(0.95) WITHIN GROUP (ORDER BY amount::float8))::numeric, 2) AS p95
+           FROM (
+               SELECT e.amount * exchange_rate(e.currency, u.base_currency, e.expense_date) AS amount
+               FROM expenses e JOIN users u ON u.id = e.user_id
+               WHERE e.user_id = $1 AND e.deleted_at IS NULL AND e.category = $2 AND e.id <> $3 AND e.expense_date >= $4
+           ) history"#,
+    )
+    .bind(user_id)
+    .bind(category)
+    .bind(exclude_id)
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
 // Daily submission tracking
 
 pub async fn check_daily_submission(
@@ -184,21 +544,3 @@ pub async fn mark_daily_submission(
     .await?;
     Ok(row)
 }
-
-pub async fn users_without_submission_today(
-    pool: &PgPool,
-    date: NaiveDate,
-) -> Result<Vec<Uuid>, AppError> {
-    let rows = sqlx::query_scalar::<_, Uuid>(
-        r#"SELECT u.id FROM users u
-           WHERE u.sns_endpoint_arn IS NOT NULL
-           AND NOT EXISTS (
-               SELECT 1 FROM daily_submissions ds
-               WHERE ds.user_id = u.id AND ds.submission_date = $1
-           )"#,
-    )
-    .bind(date)
-    .fetch_all(pool)
-    .await?;
-    Ok(rows)
-}
@@ -0,0 +1,267 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::domain::goal::*;
+use crate::errors::AppError;
+
+pub async fn create<'e, E: PgExecutor<'e>>(
+    executor: E,
+    user_id: Uuid,
+    goal: &CreateGoal,
+    start_date: NaiveDate,
+) -> Result<SavingsGoal, AppError> {
+    let row = sqlx::query_as::<_, SavingsGoal>(
+        r#"INSERT INTO savings_goals (user_id, name, target_amount, start_date, deadline, auto_contribute_percent)
+           VALUES ($1, $2, $3, $4, $5, $6)
+           RETURNING id, user_id, name, target_amount, start_date, deadline, auto_contribute_percent,
+                     achieved_on, created_at, updated_at"#,
+    )
+    .bind(user_id)
+    .bind(goal.name.trim())
+    .bind(goal.target_amount)
+    .bind(start_date)
+    .bind(goal.deadline)
+    .bind(goal.auto_contribute_percent)
+    .fetch_one(executor)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_by_id(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<Option<SavingsGoal>, AppError> {
+    let row = sqlx::query_as::<_, SavingsGoal>(
+        r#"SELECT id, user_id, name, target_amount, start_date, deadline, auto_contribute_percent,
+                  achieved_on, created_at, updated_at
+           FROM savings_goals WHERE id = $1 AND user_id = $2"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_all(pool: &PgPool, user_id: Uuid) -> Result<Vec<SavingsGoal>, AppError> {
+    let rows = sqlx::query_as::<_, SavingsGoal>(
+        r#"SELECT id, user_id, name, target_amount, start_date, deadline, auto_contribute_percent,
+                  achieved_on, created_at, updated_at
+           FROM savings_goals WHERE user_id = $1
+           ORDER BY achieved_on IS NOT NULL, deadline NULLS LAST, created_at"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn update<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: Uuid,
+    user_id: Uuid,
+    goal: &UpdateGoal,
+) -> Result<Option<SavingsGoal>, AppError> {
+    let row = sqlx::query_as::<_, SavingsGoal>(
+        r#"UPDATE savings_goals
+           SET name = COALESCE($3, name),
+               target_amount = COALESCE($4, target_amount),
+               deadline = CASE WHEN $5 THEN $6 ELSE deadline END,
+               auto_contribute_percent = COALESCE($7, auto_contribute_percent),
+               updated_at = NOW()
+           WHERE id = $1 AND user_id = $2
+           RETURNING id, user_id, name, target_amount, start_date, deadline, auto_contribute_percent,
+                     achieved_on, created_at, updated_at"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(goal.name.as_deref().map(str::trim))
+    .bind(goal.target_amount)
+    .bind(goal.deadline.is_some())
+    .bind(goal.deadline.flatten())
+    .bind(goal.auto_contribute_percent)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row)
+}
+
+pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM savings_goals WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Lock the user's row so changes to their automatic goal shares run one at
+/// a time. `FOR NO KEY UPDATE` still lets other rows reference the user.
+pub async fn lock_auto_contribute<'e, E: PgExecutor<'e>>(executor: E, user_id: Uuid) -> Result<(), AppError> {
+    sqlx::query("SELECT 1 FROM users WHERE id = $1 FOR NO KEY UPDATE")
+        .bind(user_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Sum of `auto_contribute_percent` over the user's unfinished goals, other than `exclude_id`.
+pub async fn auto_contribute_total<'e, E: PgExecutor<'e>>(
+    executor: E,
+    user_id: Uuid,
+    exclude_id: Option<Uuid>,
+) -> Result<i64, AppError> {
+    let row: (i64,) = sqlx::query_as(
+        r#"SELECT COALESCE(SUM(auto_contribute_percent), 0)::bigint
+           FROM savings_goals
+           WHERE user_id = $1 AND achieved_on IS NULL
+             AND ($2::uuid IS NULL OR id <> $2)"#,
+    )
+    .bind(user_id)
+    .bind(exclude_id)
+    .fetch_one(executor)
+    .await?;
+    Ok(row.0)
+}
+
+/// Users with at least one unfinished goal that saves automatically.
+pub async fn find_auto_contribute_user_ids(pool: &PgPool) -> Result<Vec<Uuid>, AppError> {
+    let rows: Vec<(Uuid,)> = sqlx::query_as(
+        r#"SELECT DISTINCT user_id FROM savings_goals
+           WHERE auto_contribute_percent > 0 AND achieved_on IS NULL"#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+/// Lock the user's unfinished automatic goals that had started by `period_end`.
+pub async fn lock_auto_contribute_goals<'e, E: PgExecutor<'e>>(
+    executor: E,
+    user_id: Uuid,
+    period_end: NaiveDate,
+) -> Result<Vec<SavingsGoal>, AppError> {
+    let rows = sqlx::query_as::<_, SavingsGoal>(
+        r#"SELECT id, user_id, name, target_amount, start_date, deadline, auto_contribute_percent,
+                  achieved_on, created_at, updated_at
+           FROM savings_goals
+           WHERE user_id = $1 AND auto_contribute_percent > 0 AND achieved_on IS NULL
+             AND start_date <= $2
+           ORDER BY created_at
+           FOR UPDATE"#,
+    )
+    .bind(user_id)
+    .bind(period_end)
+    .fetch_all(executor)
+    .await?;
+    Ok(rows)
+}
+
+// Contributions
+
+pub async fn saved_totals(pool: &PgPool, user_id: Uuid) -> Result<Vec<(Uuid, Decimal)>, AppError> {
+    let rows: Vec<(Uuid, Decimal)> = sqlx::query_as(
+        r#"SELECT goal_id, SUM(amount) FROM goal_contributions
+           WHERE user_id = $1
+           GROUP BY goal_id"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn saved_total<'e, E: PgExecutor<'e>>(executor: E, goal_id: Uuid) -> Result<Decimal, AppError> {
+    let row: (Decimal,) = sqlx::query_as(
+        "SELECT COALESCE(SUM(amount), 0) FROM goal_contributions WHERE goal_id = $1",
+    )
+    .bind(goal_id)
+    .fetch_one(executor)
+    .await?;
+    Ok(row.0)
+}
+
+/// Add a contribution. Returns `None` if the goal already has an automatic
+/// contribution for `period_start`.
+pub async fn add_contribution<'e, E: PgExecutor<'e>>(
+    executor: E,
+    goal_id: Uuid,
+    user_id: Uuid,
+    contribution: &CreateContribution,
+    source: ContributionSource,
+    period_start: Option<NaiveDate>,
+    contributed_on: NaiveDate,
+) -> Result<Option<GoalContribution>, AppError> {
+    let row = sqlx::query_as::<_, GoalContribution>(
+        r#"INSERT INTO goal_contributions
+               (goal_id, user_id, amount, source, period_start, note, contributed_on)
+           VALUES ($1, $2, $3, $4, $5, $6, $7)
+           ON CONFLICT (goal_id, period_start) DO NOTHING
+           RETURNING id, goal_id, amount, source, period_start, note, contributed_on, created_at"#,
+    )
+    .bind(goal_id)
+    .bind(user_id)
+    .bind(contribution.amount)
+    .bind(source.to_string())
+    .bind(period_start)
+    .bind(&contribution.note)
+    .bind(contributed_on)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_contributions(
+    pool: &PgPool,
+    goal_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<GoalContribution>, AppError> {
+    let rows = sqlx::query_as::<_, GoalContribution>(
+        r#"SELECT id, goal_id, amount, source, period_start, note, contributed_on, created_at
+           FROM goal_contributions
+           WHERE goal_id = $1 AND user_id = $2
+           ORDER BY contributed_on DESC, created_at DESC"#,
+    )
+    .bind(goal_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn delete_contribution(
+    pool: &PgPool,
+    id: Uuid,
+    goal_id: Uuid,
+    user_id: Uuid,
+) -> Result<bool, AppError> {
+    let result = sqlx::query(
+        "DELETE FROM goal_contributions WHERE id = $1 AND goal_id = $2 AND user_id = $3",
+    )
+    .bind(id)
+    .bind(goal_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Set or clear `achieved_on` after the goal's target or contributions
+/// changed. A goal that was already achieved keeps its original date.
+pub async fn refresh_achieved<'e, E: PgExecutor<'e>>(
+    executor: E,
+    goal_id: Uuid,
+    today: NaiveDate,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"UPDATE savings_goals g
+           SET achieved_on = CASE WHEN s.saved >= g.target_amount
+                                  THEN COALESCE(g.achieved_on, $2) END
+           FROM (SELECT COALESCE(SUM(amount), 0) AS saved
+                 FROM goal_contributions WHERE goal_id = $1) s
+           WHERE g.id = $1"#,
+    )
+    .bind(goal_id)
+    .bind(today)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::idempotency::*;
+use crate::errors::AppError;
+
+/// Reserve a key for a request, unless it is already in use. A key whose
+/// stored response has expired is taken over, as is one whose request
+/// never finished, e.g. because the server restarted.
+pub async fn claim(
+    pool: &PgPool,
+    user_id: Uuid,
+    key: &str,
+    request_hash: &str,
+) -> Result<bool, AppError> {
+    let row: Option<(String,)> = sqlx::query_as(
+        r#"INSERT INTO idempotency_keys (user_id, key, request_hash)
+           VALUES ($1, $2, $3)
+           ON CONFLICT (user_id, key) DO UPDATE
+           SET request_hash = EXCLUDED.request_hash,
+               status_code = NULL,
+               content_type = NULL,
+               response_body = NULL,
+               created_at = NOW()
+           WHERE idempotency_keys.created_at < NOW() - make_interval(hours => $4)
+              OR (idempotency_keys.status_code IS NULL
+                  AND idempotency_keys.created_at < NOW() - INTERVAL '5 minutes')
+           RETURNING key"#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .bind(request_hash)
+    .bind(KEY_TTL_HOURS)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.is_some())
+}
+
+pub async fn find(
+    pool: &PgPool,
+    user_id: Uuid,
+    key: &str,
+) -> Result<Option<IdempotencyRecord>, AppError> {
+    let row = sqlx::query_as::<_, IdempotencyRecord>(
+        r#"SELECT request_hash, status_code, content_type, response_body
+           FROM idempotency_keys WHERE user_id = $1 AND key = $2"#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+/// Store the response for the request that claimed the key. Nothing is
+/// stored if the key has since been taken over by a different request.
+pub async fn complete(
+    pool: &PgPool,
+    user_id: Uuid,
+    key: &str,
+    request_hash: &str,
+    status_code: i16,
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"UPDATE idempotency_keys
+           SET status_code = $4, content_type = $5, response_body = $6
+           WHERE user_id = $1 AND key = $2 AND request_hash = $3"#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .bind(request_hash)
+    .bind(status_code)
+    .bind(content_type)
+    .bind(body)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Give up a key so the request can be retried under it.
+pub async fn release(pool: &PgPool, user_id: Uuid, key: &str) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM idempotency_keys WHERE user_id = $1 AND key = $2")
+        .bind(user_id)
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_expired(pool: &PgPool) -> Result<u64, AppError> {
+    let result = sqlx::query(
+        "DELETE FROM idempotency_keys WHERE created_at < NOW() - make_interval(hours => $1)",
+    )
+    .bind(KEY_TTL_HOURS)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,37 @@
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+
+/// The subset of `fitids` the user has already imported.
+pub async fn find_existing(
+    pool: &PgPool,
+    user_id: Uuid,
+    fitids: &[String],
+) -> Result<Vec<String>, AppError> {
+    let rows = sqlx::query_scalar::<_, String>(
+        "SELECT fitid FROM imported_transactions WHERE user_id = $1 AND fitid = ANY($2)",
+    )
+    .bind(user_id)
+    .bind(fitids)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn record<'e, E: PgExecutor<'e>>(
+    executor: E,
+    user_id: Uuid,
+    fitid: &str,
+    expense_id: Uuid,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO imported_transactions (user_id, fitid, expense_id) VALUES ($1, $2, $3)",
+    )
+    .bind(user_id)
+    .bind(fitid)
+    .bind(expense_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
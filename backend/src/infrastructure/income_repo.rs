@@ -0,0 +1,161 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::analytics::{Bucket, CashflowTotals};
+use crate::domain::income::*;
+use crate::errors::AppError;
+
+pub async fn create(
+    pool: &PgPool,
+    user_id: Uuid,
+    income: &CreateIncome,
+    currency: &str,
+    income_date: NaiveDate,
+) -> Result<Income, AppError> {
+    let row = sqlx::query_as::<_, Income>(
+        r#"INSERT INTO incomes (user_id, amount, currency, source, note, income_date)
+           VALUES ($1, $2, $3, $4, $5, $6)
+           RETURNING id, user_id, amount, currency, source, note, income_date, created_at, updated_at"#,
+    )
+    .bind(user_id)
+    .bind(income.amount)
+    .bind(currency)
+    .bind(income.source.to_string())
+    .bind(&income.note)
+    .bind(income_date)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_by_id(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<Option<Income>, AppError> {
+    let row = sqlx::query_as::<_, Income>(
+        r#"SELECT id, user_id, amount, currency, source, note, income_date, created_at, updated_at
+           FROM incomes WHERE id = $1 AND user_id = $2"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_all(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: &IncomeFilter,
+) -> Result<Vec<Income>, AppError> {
+    let page = filter.page.unwrap_or(1).max(1);
+    let per_page = filter.per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    let rows = sqlx::query_as::<_, Income>(
+        r#"SELECT id, user_id, amount, currency, source, note, income_date, created_at, updated_at
+           FROM incomes
+           WHERE user_id = $1
+             AND ($2::date IS NULL OR income_date >= $2)
+             AND ($3::date IS NULL OR income_date <= $3)
+             AND ($4::text IS NULL OR source = $4)
+           ORDER BY income_date DESC, created_at DESC
+           LIMIT $5 OFFSET $6"#,
+    )
+    .bind(user_id)
+    .bind(filter.start_date)
+    .bind(filter.end_date)
+    .bind(filter.source.map(|s| s.to_string()))
+    .bind(per_page)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn update(
+    pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+    income: &UpdateIncome,
+) -> Result<Option<Income>, AppError> {
+    let row = sqlx::query_as::<_, Income>(
+        r#"UPDATE incomes
+           SET amount = COALESCE($3, amount),
+               currency = COALESCE($4, currency),
+               source = COALESCE($5, source),
+               note = COALESCE($6, note),
+               income_date = COALESCE($7, income_date),
+               updated_at = NOW()
+           WHERE id = $1 AND user_id = $2
+           RETURNING id, user_id, amount, currency, source, note, income_date, created_at, updated_at"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(income.amount)
+    .bind(&income.currency)
+    .bind(income.source.map(|s| s.to_string()))
+    .bind(&income.note)
+    .bind(income.income_date)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM incomes WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Analytics queries
+
+/// Income and expense totals in the user's base currency for every bucket
+/// between `start_date` and `end_date`, including empty ones. `start_date`
+/// should be the first day of a bucket.
+pub async fn cashflow(
+    pool: &PgPool,
+    user_id: Uuid,
+    bucket: Bucket,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<CashflowTotals>, AppError> {
+    let rows = sqlx::query_as::<_, CashflowTotals>(
+        r#"WITH periods AS (
+               SELECT generate_series(
+                   date_trunc($2, $3::date::timestamp),
+                   $4::date::timestamp,
+                   ('1 ' || $2)::interval
+               )::date AS period_start
+           ),
+           income AS (
+               SELECT date_trunc($2, i.income_date::timestamp)::date AS period_start,
+                      SUM(i.amount * exchange_rate(i.currency, u.base_currency, i.income_date)) AS total
+               FROM incomes i JOIN users u ON u.id = i.user_id
+               WHERE i.user_id = $1 AND i.income_date >= $3 AND i.income_date <= $4
+               GROUP BY 1
+           ),
+           spending AS (
+               SELECT date_trunc($2, e.expense_date::timestamp)::date AS period_start,
+                      SUM(e.amount * exchange_rate(e.currency, u.base_currency, e.expense_date)) AS total
+               FROM expenses e JOIN users u ON u.id = e.user_id
+               WHERE e.user_id = $1 AND e.deleted_at IS NULL AND e.expense_date >= $3 AND e.expense_date <= $4
+               GROUP BY 1
+           )
+           SELECT p.period_start,
+                  COALESCE(ROUND(income.total, 2), 0) AS income,
+                  COALESCE(ROUND(spending.total, 2), 0) AS expenses
+           FROM periods p
+           LEFT JOIN income USING (period_start)
+           LEFT JOIN spending USING (period_start)
+           ORDER BY p.period_start"#,
+    )
+    .bind(user_id)
+    .bind(bucket.as_sql())
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
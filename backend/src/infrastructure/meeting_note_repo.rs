@@ -1,7 +1,10 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 
 use crate::domain::meeting_note::*;
+use crate::domain::sync::SyncState;
+use crate::domain::trash::Trashed;
 use crate::errors::AppError;
 
 pub async fn create(
@@ -34,7 +37,7 @@ pub async fn find_by_id(
         r#"SELECT id, user_id, meeting_title, audio_file_url, audio_s3_key,
                   transcript_text, duration_secs, transcription_status,
                   transcription_job_name, created_at, updated_at
-           FROM meeting_notes WHERE id = $1 AND user_id = $2"#,
+           FROM meeting_notes WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"#,
     )
     .bind(id)
     .bind(user_id)
@@ -43,23 +46,41 @@ pub async fn find_by_id(
     Ok(row)
 }
 
+/// Fetch up to `limit` notes, newest first, starting after `before`.
 pub async fn find_all(
     pool: &PgPool,
     user_id: Uuid,
+    before: Option<MeetingNoteKey>,
+    limit: i64,
 ) -> Result<Vec<MeetingNote>, AppError> {
+    let (before_created, before_id) = before.unzip();
     let rows = sqlx::query_as::<_, MeetingNote>(
         r#"SELECT id, user_id, meeting_title, audio_file_url, audio_s3_key,
                   transcript_text, duration_secs, transcription_status,
                   transcription_job_name, created_at, updated_at
-           FROM meeting_notes WHERE user_id = $1
-           ORDER BY created_at DESC LIMIT 100"#,
+           FROM meeting_notes
+           WHERE user_id = $1 AND deleted_at IS NULL
+             AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3))
+           ORDER BY created_at DESC, id DESC
+           LIMIT $4"#,
     )
     .bind(user_id)
+    .bind(before_created)
+    .bind(before_id)
+    .bind(limit)
     .fetch_all(pool)
     .await?;
     Ok(rows)
 }
 
+pub async fn count(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
+    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM meeting_notes WHERE user_id = $1 AND deleted_at IS NULL")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(row.0)
+}
+
 pub async fn update_audio(
     pool: &PgPool,
     id: Uuid,
@@ -71,7 +92,7 @@ pub async fn update_audio(
     let row = sqlx::query_as::<_, MeetingNote>(
         r#"UPDATE meeting_notes
            SET audio_s3_key = $3, audio_file_url = $4, transcription_status = $5, updated_at = NOW()
-           WHERE id = $1 AND user_id = $2
+           WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
            RETURNING id, user_id, meeting_title, audio_file_url, audio_s3_key,
                      transcript_text, duration_secs, transcription_status,
                      transcription_job_name, created_at, updated_at"#,
@@ -111,11 +132,132 @@ pub async fn update_transcription(
     Ok(row)
 }
 
-pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
-    let result = sqlx::query("DELETE FROM meeting_notes WHERE id = $1 AND user_id = $2")
-        .bind(id)
-        .bind(user_id)
-        .execute(pool)
-        .await?;
+/// Move a note to the trash, leaving a tombstone for offline clients to
+/// sync. Its audio and photos are kept until the trash is purged.
+pub async fn delete<'e, E: PgExecutor<'e>>(executor: E, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+    let result = sqlx::query(
+        r#"WITH deleted AS (
+               UPDATE meeting_notes SET deleted_at = NOW()
+               WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+               RETURNING id, user_id
+           )
+           INSERT INTO sync_tombstones (entity, entity_id, user_id)
+           SELECT 'meeting_note', id, user_id FROM deleted"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(executor)
+    .await?;
     Ok(result.rows_affected() > 0)
 }
+
+/// Take a note out of the trash. Restoring counts as an edit, so it wins
+/// over offline edits made before it.
+pub async fn restore<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<MeetingNote>, AppError> {
+    let row = sqlx::query_as::<_, MeetingNote>(
+        r#"WITH restored AS (
+               UPDATE meeting_notes SET deleted_at = NULL, updated_at = NOW()
+               WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+               RETURNING id, user_id, meeting_title, audio_file_url, audio_s3_key,
+                         transcript_text, duration_secs, transcription_status,
+                         transcription_job_name, created_at, updated_at
+           ),
+           tombstone AS (
+               DELETE FROM sync_tombstones
+               WHERE entity = 'meeting_note' AND entity_id IN (SELECT id FROM restored)
+           )
+           SELECT * FROM restored"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_deleted(pool: &PgPool, user_id: Uuid) -> Result<Vec<Trashed<MeetingNote>>, AppError> {
+    let rows = sqlx::query_as::<_, Trashed<MeetingNote>>(
+        r#"SELECT id, user_id, meeting_title, audio_file_url, audio_s3_key,
+                  transcript_text, duration_secs, transcription_status,
+                  transcription_job_name, created_at, updated_at, deleted_at
+           FROM meeting_notes
+           WHERE user_id = $1 AND deleted_at IS NOT NULL
+           ORDER BY deleted_at DESC, id DESC"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Permanently delete notes trashed more than `retention_days` ago, and
+/// their photos, returning the S3 keys of their audio and photos. Their
+/// tombstones stay for clients that haven't synced since.
+pub async fn purge_deleted<'e, E: PgExecutor<'e>>(executor: E, retention_days: i32) -> Result<Vec<String>, AppError> {
+    let keys = sqlx::query_scalar::<_, String>(
+        r#"WITH purged AS (
+               DELETE FROM meeting_notes WHERE deleted_at < NOW() - make_interval(days => $1)
+               RETURNING id, audio_s3_key
+           )
+           SELECT audio_s3_key FROM purged WHERE audio_s3_key IS NOT NULL
+           UNION ALL
+           SELECT ph.s3_key FROM meeting_note_photos ph JOIN purged p ON p.id = ph.meeting_note_id"#,
+    )
+    .bind(retention_days)
+    .fetch_all(executor)
+    .await?;
+    Ok(keys)
+}
+
+/// Lock a note by id, trashed or not and whoever owns it, before applying a
+/// synced edit.
+pub async fn find_for_sync<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: Uuid,
+) -> Result<Option<SyncState>, AppError> {
+    let row = sqlx::query_as::<_, SyncState>(
+        "SELECT user_id, updated_at, deleted_at FROM meeting_notes WHERE id = $1 FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row)
+}
+
+/// Create a note with a client-chosen id, or overwrite its title and
+/// duration unless the stored copy was edited at or after `updated_at` or
+/// belongs to someone else. A trashed note is restored.
+pub async fn upsert<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: Uuid,
+    user_id: Uuid,
+    title: &str,
+    duration_secs: i32,
+    updated_at: DateTime<Utc>,
+) -> Result<Option<MeetingNote>, AppError> {
+    let row = sqlx::query_as::<_, MeetingNote>(
+        r#"INSERT INTO meeting_notes (id, user_id, meeting_title, duration_secs, transcription_status, updated_at)
+           VALUES ($1, $2, $3, $4, 'pending', $5)
+           ON CONFLICT (id) DO UPDATE
+           SET meeting_title = EXCLUDED.meeting_title,
+               duration_secs = EXCLUDED.duration_secs,
+               updated_at = EXCLUDED.updated_at,
+               deleted_at = NULL
+           WHERE meeting_notes.user_id = EXCLUDED.user_id AND meeting_notes.updated_at < EXCLUDED.updated_at
+           RETURNING id, user_id, meeting_title, audio_file_url, audio_s3_key,
+                     transcript_text, duration_secs, transcription_status,
+                     transcription_job_name, created_at, updated_at"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(title)
+    .bind(duration_secs)
+    .bind(updated_at)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row)
+}
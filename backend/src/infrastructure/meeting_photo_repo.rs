@@ -45,11 +45,17 @@ pub async fn find_by_meeting(
     Ok(rows)
 }
 
-pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<Option<String>, AppError> {
+pub async fn delete(
+    pool: &PgPool,
+    id: Uuid,
+    meeting_note_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<String>, AppError> {
     let row = sqlx::query_scalar::<_, String>(
-        "DELETE FROM meeting_note_photos WHERE id = $1 AND user_id = $2 RETURNING s3_key",
+        "DELETE FROM meeting_note_photos WHERE id = $1 AND meeting_note_id = $2 AND user_id = $3 RETURNING s3_key",
     )
     .bind(id)
+    .bind(meeting_note_id)
     .bind(user_id)
     .fetch_optional(pool)
     .await?;
@@ -4,3 +4,14 @@ pub mod expense_repo;
 pub mod meeting_note_repo;
 pub mod meeting_photo_repo;
 pub mod budget_repo;
+pub mod reminder_repo;
+pub mod category_repo;
+pub mod recurring_expense_repo;
+pub mod imported_transaction_repo;
+pub mod exchange_rate_repo;
+pub mod income_repo;
+pub mod anomaly_repo;
+pub mod goal_repo;
+pub mod attachment_repo;
+pub mod sync_repo;
+pub mod idempotency_repo;
@@ -0,0 +1,165 @@
+use chrono::NaiveDate;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::domain::recurring_expense::*;
+use crate::errors::AppError;
+
+pub async fn create(
+    pool: &PgPool,
+    user_id: Uuid,
+    input: &CreateRecurringExpense,
+    currency: &str,
+    start_date: NaiveDate,
+) -> Result<RecurringExpense, AppError> {
+    let row = sqlx::query_as::<_, RecurringExpense>(
+        r#"INSERT INTO recurring_expenses
+               (user_id, amount, currency, category, note, frequency, start_date, end_date, next_run_date)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $7)
+           RETURNING id, user_id, amount, currency, category, note, frequency, start_date, end_date,
+                     next_run_date, last_run_date, active, created_at, updated_at"#,
+    )
+    .bind(user_id)
+    .bind(input.amount)
+    .bind(currency)
+    .bind(&input.category)
+    .bind(&input.note)
+    .bind(input.frequency.to_string())
+    .bind(start_date)
+    .bind(input.end_date)
+    .fetch_one(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_by_id(
+    pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<RecurringExpense>, AppError> {
+    let row = sqlx::query_as::<_, RecurringExpense>(
+        r#"SELECT id, user_id, amount, currency, category, note, frequency, start_date, end_date,
+                  next_run_date, last_run_date, active, created_at, updated_at
+           FROM recurring_expenses WHERE id = $1 AND user_id = $2"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn find_all(pool: &PgPool, user_id: Uuid) -> Result<Vec<RecurringExpense>, AppError> {
+    let rows = sqlx::query_as::<_, RecurringExpense>(
+        r#"SELECT id, user_id, amount, currency, category, note, frequency, start_date, end_date,
+                  next_run_date, last_run_date, active, created_at, updated_at
+           FROM recurring_expenses WHERE user_id = $1
+           ORDER BY active DESC, next_run_date"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Reactivating a paused rule moves its next run forward to `resume_from`
+/// if that is later.
+pub async fn update(
+    pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+    input: &UpdateRecurringExpense,
+    resume_from: Option<NaiveDate>,
+) -> Result<Option<RecurringExpense>, AppError> {
+    let row = sqlx::query_as::<_, RecurringExpense>(
+        r#"UPDATE recurring_expenses
+           SET amount = COALESCE($3, amount),
+               category = COALESCE($4, category),
+               note = CASE WHEN $5 THEN $6 ELSE note END,
+               end_date = CASE WHEN $7 THEN $8 ELSE end_date END,
+               active = COALESCE($9, active),
+               next_run_date = CASE WHEN NOT active AND $9 THEN GREATEST(next_run_date, $10)
+                                    ELSE next_run_date END,
+               updated_at = NOW()
+           WHERE id = $1 AND user_id = $2
+           RETURNING id, user_id, amount, currency, category, note, frequency, start_date, end_date,
+                     next_run_date, last_run_date, active, created_at, updated_at"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(input.amount)
+    .bind(&input.category)
+    .bind(input.note.is_some())
+    .bind(input.note.as_ref().and_then(Option::as_deref))
+    .bind(input.end_date.is_some())
+    .bind(input.end_date.flatten())
+    .bind(input.active)
+    .bind(resume_from)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+    let result = sqlx::query("DELETE FROM recurring_expenses WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Active rules whose next run falls on or before the current date in the
+/// owner's timezone. An unknown timezone falls back to UTC, as in `User::tz`.
+pub async fn find_due_ids(pool: &PgPool) -> Result<Vec<Uuid>, AppError> {
+    let rows = sqlx::query_scalar::<_, Uuid>(
+        r#"SELECT r.id FROM recurring_expenses r
+           JOIN users u ON u.id = r.user_id
+           LEFT JOIN pg_timezone_names tz ON tz.name = u.timezone
+           WHERE r.active
+             AND r.next_run_date <= (NOW() AT TIME ZONE COALESCE(tz.name, 'UTC'))::date"#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Lock a rule for materialization. Returns `None` if it is missing or
+/// another run already holds the lock.
+pub async fn lock_for_run<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: Uuid,
+) -> Result<Option<RecurringExpense>, AppError> {
+    let row = sqlx::query_as::<_, RecurringExpense>(
+        r#"SELECT id, user_id, amount, currency, category, note, frequency, start_date, end_date,
+                  next_run_date, last_run_date, active, created_at, updated_at
+           FROM recurring_expenses WHERE id = $1
+           FOR UPDATE SKIP LOCKED"#,
+    )
+    .bind(id)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row)
+}
+
+pub async fn advance<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: Uuid,
+    next_run_date: NaiveDate,
+    last_run_date: Option<NaiveDate>,
+    active: bool,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"UPDATE recurring_expenses
+           SET next_run_date = $2, last_run_date = COALESCE($3, last_run_date),
+               active = $4, updated_at = NOW()
+           WHERE id = $1"#,
+    )
+    .bind(id)
+    .bind(next_run_date)
+    .bind(last_run_date)
+    .bind(active)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::reminder::*;
+use crate::errors::AppError;
+
+/// Record a pending reminder for the user's local date, or take over one
+/// whose send failed (once its retry is due and attempts remain) or whose
+/// run never finished. Returns `None` if there is nothing to send yet.
+pub async fn claim(
+    pool: &PgPool,
+    user_id: Uuid,
+    date: NaiveDate,
+) -> Result<Option<DailyReminder>, AppError> {
+    let row = sqlx::query_as::<_, DailyReminder>(
+        r#"INSERT INTO daily_reminders (user_id, reminder_date)
+           VALUES ($1, $2)
+           ON CONFLICT (user_id, reminder_date) DO UPDATE
+               SET status = 'pending',
+                   error = NULL,
+                   attempts = daily_reminders.attempts + 1,
+                   claimed_at = NOW()
+               WHERE daily_reminders.attempts < $3
+                 AND ((daily_reminders.status = 'failed'
+                       AND daily_reminders.claimed_at < NOW() - make_interval(
+                           mins => $4 * power(2, daily_reminders.attempts - 1)::int))
+                      OR (daily_reminders.status = 'pending'
+                          AND daily_reminders.claimed_at < NOW() - make_interval(mins => $5)))
+           RETURNING id, user_id, reminder_date, status, error, attempts, claimed_at, created_at, sent_at"#,
+    )
+    .bind(user_id)
+    .bind(date)
+    .bind(MAX_ATTEMPTS)
+    .bind(RETRY_AFTER_MINUTES)
+    .bind(STALE_CLAIM_MINUTES)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn mark_sent(pool: &PgPool, id: Uuid) -> Result<(), AppError> {
+    sqlx::query("UPDATE daily_reminders SET status = 'sent', sent_at = NOW() WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn mark_failed(pool: &PgPool, id: Uuid, error: &str) -> Result<(), AppError> {
+    sqlx::query("UPDATE daily_reminders SET status = 'failed', error = $2 WHERE id = $1")
+        .bind(id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::domain::expense::Expense;
+use crate::domain::meeting_note::MeetingNote;
+use crate::domain::sync::*;
+use crate::errors::AppError;
+
+/// The oldest transaction still running. Anything written from here on may
+/// not be visible yet.
+pub async fn snapshot_xmin(pool: &PgPool) -> Result<i64, AppError> {
+    let row: (i64,) =
+        sqlx::query_as("SELECT pg_snapshot_xmin(pg_current_snapshot())::text::bigint")
+            .fetch_one(pool)
+            .await?;
+    Ok(row.0)
+}
+
+pub async fn changed_expenses(
+    pool: &PgPool,
+    user_id: Uuid,
+    token: &SyncToken,
+    limit: i64,
+) -> Result<Vec<Versioned<Expense>>, AppError> {
+    let rows = sqlx::query_as::<_, Versioned<Expense>>(
+        r#"SELECT id, user_id, amount, currency, category, note, tags, expense_date,
+                  created_at, updated_at, row_version
+           FROM expenses
+           WHERE user_id = $1 AND row_version > $2 AND sync_xid >= $3::bigint::text::xid8
+             AND deleted_at IS NULL
+           ORDER BY row_version
+           LIMIT $4"#,
+    )
+    .bind(user_id)
+    .bind(token.after)
+    .bind(token.since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn changed_meeting_notes(
+    pool: &PgPool,
+    user_id: Uuid,
+    token: &SyncToken,
+    limit: i64,
+) -> Result<Vec<Versioned<MeetingNote>>, AppError> {
+    let rows = sqlx::query_as::<_, Versioned<MeetingNote>>(
+        r#"SELECT id, user_id, meeting_title, audio_file_url, audio_s3_key,
+                  transcript_text, duration_secs, transcription_status,
+                  transcription_job_name, created_at, updated_at, row_version
+           FROM meeting_notes
+           WHERE user_id = $1 AND row_version > $2 AND sync_xid >= $3::bigint::text::xid8
+             AND deleted_at IS NULL
+           ORDER BY row_version
+           LIMIT $4"#,
+    )
+    .bind(user_id)
+    .bind(token.after)
+    .bind(token.since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn tombstones(
+    pool: &PgPool,
+    user_id: Uuid,
+    token: &SyncToken,
+    limit: i64,
+) -> Result<Vec<Versioned<Tombstone>>, AppError> {
+    let rows = sqlx::query_as::<_, Versioned<Tombstone>>(
+        r#"SELECT entity, entity_id, deleted_at, row_version
+           FROM sync_tombstones
+           WHERE user_id = $1 AND row_version > $2 AND sync_xid >= $3::bigint::text::xid8
+           ORDER BY row_version
+           LIMIT $4"#,
+    )
+    .bind(user_id)
+    .bind(token.after)
+    .bind(token.since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// When the user deleted the row, if they did.
+pub async fn deleted_at<'e, E: PgExecutor<'e>>(
+    executor: E,
+    entity: SyncEntity,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    let row: Option<(DateTime<Utc>,)> = sqlx::query_as(
+        "SELECT deleted_at FROM sync_tombstones WHERE entity = $1 AND entity_id = $2 AND user_id = $3",
+    )
+    .bind(entity.as_str())
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row.map(|r| r.0))
+}
+
+/// Forget a deletion, when a later offline edit brings the row back.
+pub async fn remove_tombstone<'e, E: PgExecutor<'e>>(
+    executor: E,
+    entity: SyncEntity,
+    id: Uuid,
+) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM sync_tombstones WHERE entity = $1 AND entity_id = $2")
+        .bind(entity.as_str())
+        .bind(id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
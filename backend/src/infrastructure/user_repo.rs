@@ -6,7 +6,7 @@ use crate::errors::AppError;
 
 pub async fn find_by_cognito_sub(pool: &PgPool, cognito_sub: &str) -> Result<Option<User>, AppError> {
     let user = sqlx::query_as::<_, User>(
-        "SELECT id, cognito_sub, email, name, notification_time, sns_endpoint_arn, created_at FROM users WHERE cognito_sub = $1"
+        "SELECT id, cognito_sub, email, name, notification_time, timezone, base_currency, anomaly_alerts, sns_endpoint_arn, created_at FROM users WHERE cognito_sub = $1"
     )
     .bind(cognito_sub)
     .fetch_optional(pool)
@@ -23,7 +23,7 @@ pub async fn create(
     let user = sqlx::query_as::<_, User>(
         r#"INSERT INTO users (cognito_sub, email, name)
            VALUES ($1, $2, $3)
-           RETURNING id, cognito_sub, email, name, notification_time, sns_endpoint_arn, created_at"#,
+           RETURNING id, cognito_sub, email, name, notification_time, timezone, base_currency, anomaly_alerts, sns_endpoint_arn, created_at"#,
     )
     .bind(cognito_sub)
     .bind(email)
@@ -35,7 +35,7 @@ pub async fn create(
 
 pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<User>, AppError> {
     let user = sqlx::query_as::<_, User>(
-        "SELECT id, cognito_sub, email, name, notification_time, sns_endpoint_arn, created_at FROM users WHERE id = $1"
+        "SELECT id, cognito_sub, email, name, notification_time, timezone, base_currency, anomaly_alerts, sns_endpoint_arn, created_at FROM users WHERE id = $1"
     )
     .bind(id)
     .fetch_optional(pool)
@@ -51,13 +51,19 @@ pub async fn update_profile(
     let user = sqlx::query_as::<_, User>(
         r#"UPDATE users
            SET name = COALESCE($2, name),
-               notification_time = COALESCE($3, notification_time)
+               notification_time = COALESCE($3, notification_time),
+               timezone = COALESCE($4, timezone),
+               base_currency = COALESCE($5, base_currency),
+               anomaly_alerts = COALESCE($6, anomaly_alerts)
            WHERE id = $1
-           RETURNING id, cognito_sub, email, name, notification_time, sns_endpoint_arn, created_at"#,
+           RETURNING id, cognito_sub, email, name, notification_time, timezone, base_currency, anomaly_alerts, sns_endpoint_arn, created_at"#,
     )
     .bind(user_id)
     .bind(&profile.name)
     .bind(profile.notification_time)
+    .bind(&profile.timezone)
+    .bind(&profile.base_currency)
+    .bind(profile.anomaly_alerts)
     .fetch_one(pool)
     .await?;
     Ok(user)
@@ -78,7 +84,7 @@ pub async fn update_sns_endpoint(
 
 pub async fn find_all_with_sns(pool: &PgPool) -> Result<Vec<User>, AppError> {
     let users = sqlx::query_as::<_, User>(
-        "SELECT id, cognito_sub, email, name, notification_time, sns_endpoint_arn, created_at FROM users WHERE sns_endpoint_arn IS NOT NULL"
+        "SELECT id, cognito_sub, email, name, notification_time, timezone, base_currency, anomaly_alerts, sns_endpoint_arn, created_at FROM users WHERE sns_endpoint_arn IS NOT NULL"
     )
     .fetch_all(pool)
     .await?;
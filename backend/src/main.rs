@@ -4,19 +4,26 @@ mod domain;
 mod errors;
 mod infrastructure;
 mod middleware;
+mod scheduler;
 mod services;
 
 use axum::{
+    extract::DefaultBodyLimit,
     middleware::from_fn_with_state,
     routing::{get, post, put, delete},
     Router,
 };
+use std::sync::Arc;
+
 use sqlx::PgPool;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
 use crate::config::Config;
+use crate::services::attachment_service::MAX_ATTACHMENT_BYTES;
 use crate::services::auth_service::AuthService;
+use crate::services::ocr::{OcrEngine, TesseractOcr};
+use crate::services::storage_service::StorageService;
 
 #[tokio::main]
 async fn main() {
@@ -43,13 +50,34 @@ async fn main() {
 
     // Initialize meeting service if S3 bucket configured
     let meeting_service: Option<services::meeting_service::MeetingService> = if let Some(bucket) = &config.meeting_audio_s3_bucket {
-        Some(services::meeting_service::MeetingService::new(bucket.clone(), config.cognito_region.clone()).await)
+        let storage = StorageService::new(bucket.clone(), config.s3_endpoint_url.as_deref()).await;
+        Some(services::meeting_service::MeetingService::new(storage, config.cognito_region.clone()).await)
     } else {
         tracing::warn!("MEETING_AUDIO_S3_BUCKET not set – meeting audio upload disabled");
         None
     };
     let meeting_state = (pool.clone(), meeting_service.clone());
 
+    // Expense attachments share the S3 setup but may use their own bucket
+    let attachment_storage = match &config.attachments_s3_bucket {
+        Some(bucket) => Some(StorageService::new(bucket.clone(), config.s3_endpoint_url.as_deref()).await),
+        None => {
+            tracing::warn!("ATTACHMENTS_S3_BUCKET not set – expense attachments disabled");
+            None
+        }
+    };
+    let attachment_state = (pool.clone(), attachment_storage.clone());
+
+    // Background jobs (daily reminders, recurring expenses, goal contributions,
+    // idempotency key cleanup, trash purge)
+    let meeting_storage = meeting_service.as_ref().map(|s| s.storage().clone());
+    let _scheduler = scheduler::start(pool.clone(), config.clone(), attachment_storage, meeting_storage)
+        .await
+        .expect("Failed to start scheduler");
+
+    let ocr: Arc<dyn OcrEngine> =
+        Arc::new(TesseractOcr::new(config.tesseract_path.clone(), config.ocr_languages.clone()));
+
     // Notification registration needs both pool and config
     let notification_routes = Router::new()
         .route(
@@ -76,21 +104,90 @@ async fn main() {
     let api_routes = Router::new()
         .route("/auth/me", get(api::auth::me))
         .route("/users/profile", put(api::users::update_profile))
-        .route("/expenses", post(api::expenses::create_expense))
+        .route("/expenses", post(api::expenses::create_expense).with_state((pool.clone(), config.clone())))
         .route("/expenses", get(api::expenses::list_expenses))
+        .route("/expenses/parse", post(api::expenses::quick_add).with_state((pool.clone(), config.clone())))
+        .route("/expenses/search", get(api::expenses::search_expenses))
+        .route("/expenses/anomalies", get(api::expenses::list_anomalies))
+        .route("/expenses/export", get(api::expenses::export_expenses))
+        .route("/expenses/import", post(api::expenses::import_expenses).with_state((pool.clone(), config.clone())))
+        .route("/expenses/import/statement", post(api::expenses::import_statement).with_state((pool.clone(), config.clone())))
+        .route(
+            "/expenses/receipts/scan",
+            post(api::expenses::scan_receipt)
+                .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_BYTES + 64 * 1024))
+                .with_state((pool.clone(), ocr)),
+        )
         .route("/expenses/:id", get(api::expenses::get_expense))
-        .route("/expenses/:id", put(api::expenses::update_expense))
+        .route("/expenses/:id", put(api::expenses::update_expense).with_state((pool.clone(), config.clone())))
         .route("/expenses/:id", delete(api::expenses::delete_expense))
+        .route("/expenses/:id/restore", post(api::trash::restore_expense))
+        .route(
+            "/expenses/:id/attachments",
+            get(api::attachments::list_attachments)
+                .post(api::attachments::upload_attachment)
+                .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_BYTES + 64 * 1024))
+                .with_state(attachment_state.clone()),
+        )
+        .route(
+            "/expenses/:id/attachments/:attachment_id",
+            delete(api::attachments::delete_attachment).with_state(attachment_state),
+        )
+        .route("/recurring-expenses", get(api::recurring_expenses::list_recurring_expenses))
+        .route(
+            "/recurring-expenses",
+            post(api::recurring_expenses::create_recurring_expense).with_state((pool.clone(), config.clone())),
+        )
+        .route(
+            "/recurring-expenses/:id",
+            get(api::recurring_expenses::get_recurring_expense)
+                .put(api::recurring_expenses::update_recurring_expense)
+                .delete(api::recurring_expenses::delete_recurring_expense),
+        )
+        .route("/incomes", get(api::incomes::list_incomes).post(api::incomes::create_income))
+        .route(
+            "/incomes/:id",
+            get(api::incomes::get_income)
+                .put(api::incomes::update_income)
+                .delete(api::incomes::delete_income),
+        )
+        .route("/goals", get(api::goals::list_goals).post(api::goals::create_goal))
+        .route(
+            "/goals/:id",
+            get(api::goals::get_goal)
+                .put(api::goals::update_goal)
+                .delete(api::goals::delete_goal),
+        )
+        .route(
+            "/goals/:id/contributions",
+            get(api::goals::list_contributions).post(api::goals::add_contribution),
+        )
+        .route("/goals/:id/contributions/:contribution_id", delete(api::goals::delete_contribution))
+        .route("/categories", get(api::categories::list_categories).post(api::categories::create_category))
+        .route("/categories/:id", put(api::categories::update_category).delete(api::categories::delete_category))
         .route("/analytics/daily", get(api::analytics::daily))
         .route("/analytics/weekly", get(api::analytics::weekly))
+        .route("/analytics/weekly/compare", get(api::analytics::weekly_comparison))
         .route("/analytics/monthly", get(api::analytics::monthly))
+        .route("/analytics/monthly/compare", get(api::analytics::monthly_comparison))
         .route("/analytics/category", get(api::analytics::category))
+        .route("/analytics/cashflow", get(api::analytics::cashflow))
+        .route("/analytics/timeseries", get(api::analytics::timeseries))
+        .route("/analytics/forecast", get(api::analytics::forecast))
         .route("/daily-status", get(api::daily_status::check_status))
         .route("/daily-status/submit", post(api::daily_status::submit_day))
         .route("/budget", get(api::budget::get_budget).put(api::budget::set_budget))
+        .route("/budget/history", get(api::budget::budget_history))
+        .route("/budget/categories", put(api::budget::set_category_budget))
+        .route("/budget/categories/:category", delete(api::budget::delete_category_budget))
+        .route("/trash", get(api::trash::list_trash).with_state((pool.clone(), config.clone())))
+        .route("/meeting-notes/:id/restore", post(api::trash::restore_meeting_note))
+        .route("/sync", post(api::sync::sync).with_state((pool.clone(), config.clone())))
         .with_state(pool.clone())
         .merge(notification_routes)
         .merge(meeting_routes)
+        // Layers run bottom up: authenticate, then check the idempotency key
+        .layer(from_fn_with_state(pool.clone(), middleware::idempotency::idempotency_middleware))
         .layer(from_fn_with_state(auth_state, middleware::auth::auth_middleware));
 
     let app = Router::new()
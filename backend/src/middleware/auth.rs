@@ -7,7 +7,7 @@ use sqlx::PgPool;
 
 use crate::domain::user::User;
 use crate::errors::AppError;
-use crate::infrastructure::user_repo;
+use crate::infrastructure::{category_repo, user_repo};
 use crate::services::auth_service::AuthService;
 
 #[derive(Clone)]
@@ -38,7 +38,9 @@ pub async fn auth_middleware(
             let name = claims.name
                 .or(claims.cognito_username)
                 .unwrap_or_else(|| "User".to_string());
-            user_repo::create(&pool, &claims.sub, &email, &name).await?
+            let user = user_repo::create(&pool, &claims.sub, &email, &name).await?;
+            category_repo::seed_defaults(&pool, user.id).await?;
+            user
         }
     };
 
@@ -0,0 +1,168 @@
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body_util::LengthLimitError;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::domain::idempotency::{IdempotencyRecord, MAX_KEY_LEN};
+use crate::errors::AppError;
+use crate::infrastructure::idempotency_repo;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::attachment_service::MAX_ATTACHMENT_BYTES;
+
+pub const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+/// Set on responses that were replayed rather than produced again.
+pub const IDEMPOTENT_REPLAYED: &str = "Idempotent-Replayed";
+
+/// Bodies are buffered to hash them, up to the largest upload any route takes.
+const MAX_BODY_BYTES: usize = MAX_ATTACHMENT_BYTES + 64 * 1024;
+
+/// Run a mutating request sent with an `Idempotency-Key` header at most
+/// once per user and key. Repeats of the request get the stored response;
+/// reusing the key for a different request is refused. Must run after
+/// `auth_middleware`.
+pub async fn idempotency_middleware(
+    State(pool): State<PgPool>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let mutating = matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE);
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY).filter(|_| mutating) else {
+        return Ok(next.run(req).await);
+    };
+    let key = key
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|k| !k.is_empty() && k.len() <= MAX_KEY_LEN)
+        .ok_or_else(|| {
+            AppError::Validation(format!("{} must be 1 to {} characters", IDEMPOTENCY_KEY, MAX_KEY_LEN))
+        })?
+        .to_string();
+    let user_id = req
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .map(|AuthenticatedUser(user)| user.id)
+        .ok_or(AppError::Unauthorized)?;
+
+    let (parts, body) = req.into_parts();
+    let body = read_body(body, MAX_BODY_BYTES).await?;
+    let hash = request_hash(&parts.method, parts.uri.path_and_query().map_or("", |p| p.as_str()), &body);
+
+    if !idempotency_repo::claim(&pool, user_id, &key, &hash).await? {
+        return match idempotency_repo::find(&pool, user_id, &key).await? {
+            Some(record) if record.request_hash != hash => Err(AppError::Validation(format!(
+                "{} was already used for a different request",
+                IDEMPOTENCY_KEY
+            ))),
+            Some(record) if record.status_code.is_some() => Ok(replay(record)),
+            // Still running, or released by a failure just now
+            _ => Err(AppError::Conflict(format!(
+                "A request with this {} is in progress; retry shortly",
+                IDEMPOTENCY_KEY
+            ))),
+        };
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    // Server errors aren't the request's fault, so let a retry run it again
+    if response.status().is_server_error() {
+        release(&pool, user_id, &key).await;
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            release(&pool, user_id, &key).await;
+            return Err(AppError::Internal(format!("Failed to read response: {}", e)));
+        }
+    };
+    let content_type = parts.headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok());
+    if let Err(e) = idempotency_repo::complete(
+        &pool,
+        user_id,
+        &key,
+        &hash,
+        parts.status.as_u16() as i16,
+        content_type,
+        &body,
+    )
+    .await
+    {
+        tracing::error!("Failed to store idempotent response for user {}: {}", user_id, e);
+        release(&pool, user_id, &key).await;
+    }
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// Buffer a request body, refusing one over `limit` bytes with a 413.
+async fn read_body(body: Body, limit: usize) -> Result<Bytes, AppError> {
+    to_bytes(body, limit).await.map_err(|e| {
+        if std::error::Error::source(&e).is_some_and(|s| s.is::<LengthLimitError>()) {
+            AppError::PayloadTooLarge("Request body is too large".into())
+        } else {
+            AppError::Validation(format!("Failed to read request body: {}", e))
+        }
+    })
+}
+
+/// Identifies a request by method, path, query and body.
+fn request_hash(method: &Method, path_and_query: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b"\n");
+    hasher.update(path_and_query);
+    hasher.update(b"\n");
+    hasher.update(body);
+    format!("{:x}", hasher.finalize())
+}
+
+fn replay(record: IdempotencyRecord) -> Response {
+    let status = record
+        .status_code
+        .and_then(|code| StatusCode::from_u16(code as u16).ok())
+        .unwrap_or(StatusCode::OK);
+    let mut response = (status, record.response_body.unwrap_or_default()).into_response();
+    let headers = response.headers_mut();
+    headers.remove(header::CONTENT_TYPE);
+    if let Some(value) = record.content_type.and_then(|v| HeaderValue::from_str(&v).ok()) {
+        headers.insert(header::CONTENT_TYPE, value);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    response
+}
+
+async fn release(pool: &PgPool, user_id: uuid::Uuid, key: &str) {
+    if let Err(e) = idempotency_repo::release(pool, user_id, key).await {
+        tracing::error!("Failed to release idempotency key for user {}: {}", user_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_covers_method_path_and_body() {
+        let hash = request_hash(&Method::POST, "/api/expenses", b"{\"amount\":\"10\"}");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, request_hash(&Method::POST, "/api/expenses", b"{\"amount\":\"10\"}"));
+        assert_ne!(hash, request_hash(&Method::POST, "/api/expenses", b"{\"amount\":\"11\"}"));
+        assert_ne!(hash, request_hash(&Method::PUT, "/api/expenses", b"{\"amount\":\"10\"}"));
+        assert_ne!(hash, request_hash(&Method::POST, "/api/meeting-notes", b"{\"amount\":\"10\"}"));
+    }
+
+    #[tokio::test]
+    async fn oversized_body_is_payload_too_large() {
+        assert_eq!(&read_body(Body::from("0123"), 4).await.unwrap()[..], b"0123");
+        let err = read_body(Body::from("01234"), 4).await.unwrap_err();
+        assert!(matches!(err, AppError::PayloadTooLarge(_)));
+    }
+}
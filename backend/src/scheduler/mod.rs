@@ -0,0 +1,113 @@
+use sqlx::PgPool;
+use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
+
+use crate::config::Config;
+use crate::infrastructure::idempotency_repo;
+use crate::services::storage_service::StorageService;
+use crate::services::{goal_service, notification_service, recurring_expense_service, trash_service};
+
+/// Reminders are checked every minute so each user is notified close to
+/// their own `notification_time`.
+const REMINDER_SCHEDULE: &str = "0 * * * * *";
+
+/// Recurring expenses are materialized every 15 minutes, so each rule runs
+/// shortly after midnight in its owner's timezone.
+const RECURRING_EXPENSE_SCHEDULE: &str = "0 */15 * * * *";
+
+/// Unspent budgets are saved into goals hourly, so each period is picked up
+/// within an hour of ending in its owner's timezone.
+const GOAL_CONTRIBUTION_SCHEDULE: &str = "0 5 * * * *";
+
+/// Stored idempotent responses are kept for 24 hours; expired ones are
+/// cleared hourly.
+const IDEMPOTENCY_CLEANUP_SCHEDULE: &str = "0 35 * * * *";
+
+/// Items trashed longer than `TRASH_RETENTION_DAYS` are purged, along with
+/// their files, once a day.
+const TRASH_PURGE_SCHEDULE: &str = "0 20 3 * * *";
+
+pub async fn start(
+    pool: PgPool,
+    config: Config,
+    attachments: Option<StorageService>,
+    meeting_storage: Option<StorageService>,
+) -> Result<JobScheduler, JobSchedulerError> {
+    let scheduler = JobScheduler::new().await?;
+    let retention_days = config.trash_retention_days;
+    let recurring_config = config.clone();
+
+    let reminder_pool = pool.clone();
+    scheduler
+        .add(Job::new_async(REMINDER_SCHEDULE, move |_id, _scheduler| {
+            let pool = reminder_pool.clone();
+            let config = config.clone();
+            Box::pin(async move {
+                if let Err(e) = notification_service::send_daily_reminders(&pool, &config).await {
+                    tracing::error!("Daily reminder job failed: {}", e);
+                }
+            })
+        })?)
+        .await?;
+
+    let recurring_pool = pool.clone();
+    scheduler
+        .add(Job::new_async(RECURRING_EXPENSE_SCHEDULE, move |_id, _scheduler| {
+            let pool = recurring_pool.clone();
+            let config = recurring_config.clone();
+            Box::pin(async move {
+                if let Err(e) = recurring_expense_service::materialize_due(&pool, &config).await {
+                    tracing::error!("Recurring expense job failed: {}", e);
+                }
+            })
+        })?)
+        .await?;
+
+    let goal_pool = pool.clone();
+    scheduler
+        .add(Job::new_async(GOAL_CONTRIBUTION_SCHEDULE, move |_id, _scheduler| {
+            let pool = goal_pool.clone();
+            Box::pin(async move {
+                if let Err(e) = goal_service::contribute_unspent_budgets(&pool).await {
+                    tracing::error!("Goal contribution job failed: {}", e);
+                }
+            })
+        })?)
+        .await?;
+
+    let idempotency_pool = pool.clone();
+    scheduler
+        .add(Job::new_async(IDEMPOTENCY_CLEANUP_SCHEDULE, move |_id, _scheduler| {
+            let pool = idempotency_pool.clone();
+            Box::pin(async move {
+                match idempotency_repo::delete_expired(&pool).await {
+                    Ok(deleted) => tracing::debug!("Removed {} expired idempotency keys", deleted),
+                    Err(e) => tracing::error!("Idempotency key cleanup failed: {}", e),
+                }
+            })
+        })?)
+        .await?;
+
+    let trash_pool = pool.clone();
+    scheduler
+        .add(Job::new_async(TRASH_PURGE_SCHEDULE, move |_id, _scheduler| {
+            let pool = trash_pool.clone();
+            let attachments = attachments.clone();
+            let meeting_storage = meeting_storage.clone();
+            Box::pin(async move {
+                if let Err(e) = trash_service::purge_expired(
+                    &pool,
+                    retention_days,
+                    attachments.as_ref(),
+                    meeting_storage.as_ref(),
+                )
+                .await
+                {
+                    tracing::error!("Trash purge failed: {}", e);
+                }
+            })
+        })?)
+        .await?;
+
+    scheduler.start().await?;
+    Ok(scheduler)
+}
@@ -1,56 +1,300 @@
-use chrono::{Datelike, NaiveDate, Utc};
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::domain::expense::ExpenseSummary;
+use crate::domain::analytics::*;
+use crate::domain::category::normalize_name;
+use crate::domain::expense::{CategorySummary, ExpenseSummary};
+use crate::domain::user::User;
 use crate::errors::AppError;
-use crate::infrastructure::expense_repo;
+use crate::infrastructure::{expense_repo, income_repo};
+
+/// Upper bound on the number of buckets in a single time-bucketed report.
+const MAX_BUCKETS: i64 = 400;
 
 pub async fn daily_summary(
     pool: &PgPool,
-    user_id: Uuid,
+    user: &User,
     date: Option<NaiveDate>,
 ) -> Result<ExpenseSummary, AppError> {
-    let date = date.unwrap_or_else(|| Utc::now().date_naive());
-    expense_repo::daily_summary(pool, user_id, date).await
+    let date = date.unwrap_or_else(|| user.today());
+    expense_repo::daily_summary(pool, user.id, date).await
 }
 
 pub async fn weekly_summary(
     pool: &PgPool,
-    user_id: Uuid,
+    user: &User,
     date: Option<NaiveDate>,
 ) -> Result<ExpenseSummary, AppError> {
-    let date = date.unwrap_or_else(|| Utc::now().date_naive());
-    let weekday = date.weekday().num_days_from_monday();
-    let start = date - chrono::Duration::days(weekday as i64);
-    let end = start + chrono::Duration::days(6);
-    expense_repo::range_summary(pool, user_id, start, end).await
+    let date = date.unwrap_or_else(|| user.today());
+    let (start, end) = week_bounds(date);
+    expense_repo::range_summary(pool, user.id, start, end).await
 }
 
 pub async fn monthly_summary(
     pool: &PgPool,
-    user_id: Uuid,
+    user: &User,
     date: Option<NaiveDate>,
 ) -> Result<ExpenseSummary, AppError> {
-    let date = date.unwrap_or_else(|| Utc::now().date_naive());
+    let date = date.unwrap_or_else(|| user.today());
+    let (start, end) = month_bounds(date);
+    expense_repo::range_summary(pool, user.id, start, end).await
+}
+
+/// Monday and Sunday of the week containing `date`.
+pub fn week_bounds(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let weekday = date.weekday().num_days_from_monday();
+    let start = date - chrono::Duration::days(weekday as i64);
+    (start, start + chrono::Duration::days(6))
+}
+
+/// First and last day of the month containing `date`.
+pub fn month_bounds(date: NaiveDate) -> (NaiveDate, NaiveDate) {
     let start = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap();
     let end = if date.month() == 12 {
         NaiveDate::from_ymd_opt(date.year() + 1, 1, 1).unwrap()
     } else {
         NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1).unwrap()
     } - chrono::Duration::days(1);
-    expense_repo::range_summary(pool, user_id, start, end).await
+    (start, end)
 }
 
 pub async fn category_summary(
     pool: &PgPool,
-    user_id: Uuid,
+    user: &User,
     start_date: Option<NaiveDate>,
     end_date: Option<NaiveDate>,
 ) -> Result<ExpenseSummary, AppError> {
-    let end = end_date.unwrap_or_else(|| Utc::now().date_naive());
+    let end = end_date.unwrap_or_else(|| user.today());
     let start = start_date.unwrap_or_else(|| {
         NaiveDate::from_ymd_opt(end.year(), end.month(), 1).unwrap()
     });
-    expense_repo::range_summary(pool, user_id, start, end).await
+    expense_repo::range_summary(pool, user.id, start, end).await
+}
+
+/// Income, expenses and net savings per bucket. Without dates this covers
+/// the last 30 days, 12 weeks or 12 months up to today. `start_date` is moved
+/// back to the start of its bucket.
+pub async fn cashflow(
+    pool: &PgPool,
+    user: &User,
+    query: &CashflowQuery,
+) -> Result<CashflowReport, AppError> {
+    let (start, end) = bucket_range(user, query.bucket, query.start_date, query.end_date)?;
+
+    let periods: Vec<CashflowPeriod> = income_repo::cashflow(pool, user.id, query.bucket, start, end)
+        .await?
+        .into_iter()
+        .map(CashflowPeriod::from)
+        .collect();
+
+    let total_income: Decimal = periods.iter().map(|p| p.income).sum();
+    let total_expenses: Decimal = periods.iter().map(|p| p.expenses).sum();
+    let net = total_income - total_expenses;
+
+    Ok(CashflowReport {
+        currency: user.base_currency.clone(),
+        bucket: query.bucket,
+        start_date: start,
+        end_date: end,
+        total_income,
+        total_expenses,
+        net,
+        savings_rate: savings_rate(total_income, net),
+        periods,
+    })
+}
+
+/// This week against last week and the same week a year ago (52 weeks
+/// earlier, so weekdays line up).
+pub async fn weekly_comparison(
+    pool: &PgPool,
+    user: &User,
+    query: &CompareQuery,
+) -> Result<PeriodComparison, AppError> {
+    let date = query.date.unwrap_or_else(|| user.today());
+    let current = week_bounds(date);
+    let previous = week_bounds(date - chrono::Duration::weeks(1));
+    let last_year = week_bounds(date - chrono::Duration::weeks(52));
+    compare(pool, user, date, query.to_date, current, previous, last_year).await
+}
+
+/// This month against last month and the same month a year ago.
+pub async fn monthly_comparison(
+    pool: &PgPool,
+    user: &User,
+    query: &CompareQuery,
+) -> Result<PeriodComparison, AppError> {
+    let date = query.date.unwrap_or_else(|| user.today());
+    let current = month_bounds(date);
+    let previous = month_bounds(current.0 - chrono::Duration::days(1));
+    let last_year = month_bounds(current.0 - chrono::Months::new(12));
+    compare(pool, user, date, query.to_date, current, previous, last_year).await
+}
+
+async fn compare(
+    pool: &PgPool,
+    user: &User,
+    date: NaiveDate,
+    to_date: bool,
+    current: (NaiveDate, NaiveDate),
+    previous: (NaiveDate, NaiveDate),
+    last_year: (NaiveDate, NaiveDate),
+) -> Result<PeriodComparison, AppError> {
+    // Cut every period to as many days as have passed in the current one
+    let elapsed = date - current.0;
+    let cut = |(start, end): (NaiveDate, NaiveDate)| {
+        if to_date {
+            (start, (start + elapsed).min(end))
+        } else {
+            (start, end)
+        }
+    };
+
+    let current = period_summary(pool, user.id, cut(current)).await?;
+    let previous = period_summary(pool, user.id, cut(previous)).await?;
+    let last_year = period_summary(pool, user.id, cut(last_year)).await?;
+
+    let total_in = |period: &PeriodSummary, category: &str| {
+        period
+            .summary
+            .by_category
+            .iter()
+            .find(|c| c.category == category)
+            .map(|c| c.total)
+            .unwrap_or(Decimal::ZERO)
+    };
+    let mut categories: Vec<&str> = Vec::new();
+    for period in [&current, &previous, &last_year] {
+        for c in &period.summary.by_category {
+            if !categories.contains(&c.category.as_str()) {
+                categories.push(&c.category);
+            }
+        }
+    }
+    let mut by_category: Vec<CategoryComparison> = categories
+        .into_iter()
+        .map(|category| {
+            let (now, prev, year) = (
+                total_in(&current, category),
+                total_in(&previous, category),
+                total_in(&last_year, category),
+            );
+            CategoryComparison {
+                category: category.to_string(),
+                current: now,
+                previous: prev,
+                last_year: year,
+                vs_previous: Delta::between(prev, now),
+                vs_last_year: Delta::between(year, now),
+            }
+        })
+        .collect();
+    by_category.sort_by(|a, b| b.current.cmp(&a.current).then(b.previous.cmp(&a.previous)));
+
+    Ok(PeriodComparison {
+        currency: user.base_currency.clone(),
+        vs_previous: Delta::between(previous.summary.total, current.summary.total),
+        vs_last_year: Delta::between(last_year.summary.total, current.summary.total),
+        current,
+        previous,
+        last_year,
+        by_category,
+    })
+}
+
+async fn period_summary(
+    pool: &PgPool,
+    user_id: Uuid,
+    (start, end): (NaiveDate, NaiveDate),
+) -> Result<PeriodSummary, AppError> {
+    Ok(PeriodSummary {
+        start_date: start,
+        end_date: end,
+        summary: expense_repo::range_summary(pool, user_id, start, end).await?,
+    })
+}
+
+/// Expense totals per bucket with a per-category breakdown, from a single
+/// query. Defaults match [`cashflow`].
+pub async fn timeseries(
+    pool: &PgPool,
+    user: &User,
+    query: &TimeseriesQuery,
+) -> Result<Timeseries, AppError> {
+    let (start, end) = bucket_range(user, query.bucket, query.start, query.end)?;
+    let category = query.category.as_deref().map(normalize_name);
+
+    let rows = expense_repo::timeseries(pool, user.id, query.bucket, start, end, category.as_deref()).await?;
+
+    let mut buckets: Vec<TimeseriesBucket> = Vec::new();
+    for row in rows {
+        if buckets.last().map(|b| b.period_start) != Some(row.period_start) {
+            buckets.push(TimeseriesBucket {
+                period_start: row.period_start,
+                total: Decimal::ZERO,
+                count: 0,
+                by_category: Vec::new(),
+            });
+        }
+        let Some(category) = row.category else { continue };
+        let bucket = buckets.last_mut().expect("bucket was just pushed");
+        bucket.total += row.total;
+        bucket.count += row.count;
+        bucket.by_category.push(CategorySummary {
+            category,
+            total: row.total,
+            count: row.count,
+        });
+    }
+
+    Ok(Timeseries {
+        currency: user.base_currency.clone(),
+        bucket: query.bucket,
+        start,
+        end,
+        category,
+        total: buckets.iter().map(|b| b.total).sum(),
+        count: buckets.iter().map(|b| b.count).sum(),
+        buckets,
+    })
+}
+
+/// Resolve an optional date range for a bucketed report, with the start
+/// aligned to its bucket.
+fn bucket_range(
+    user: &User,
+    bucket: Bucket,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+) -> Result<(NaiveDate, NaiveDate), AppError> {
+    let end = end_date.unwrap_or_else(|| user.today());
+    let start = match start_date {
+        Some(start) => start,
+        None => match bucket {
+            Bucket::Day => end - chrono::Duration::days(29),
+            Bucket::Week => end - chrono::Duration::weeks(11),
+            Bucket::Month => end - chrono::Months::new(11),
+        },
+    };
+    let start = bucket.start_of(start);
+    if start > end {
+        return Err(AppError::Validation("start_date cannot be after end_date".into()));
+    }
+
+    let buckets = match bucket {
+        Bucket::Day => (end - start).num_days() + 1,
+        Bucket::Week => (end - start).num_days() / 7 + 1,
+        Bucket::Month => {
+            (end.year() - start.year()) as i64 * 12 + end.month() as i64 - start.month() as i64 + 1
+        }
+    };
+    if buckets > MAX_BUCKETS {
+        return Err(AppError::Validation(format!(
+            "Range is too long: at most {} buckets are allowed",
+            MAX_BUCKETS
+        )));
+    }
+    Ok((start, end))
 }
@@ -0,0 +1,161 @@
+use chrono::Months;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::anomaly::*;
+use crate::domain::expense::Expense;
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::{anomaly_repo, exchange_rate_repo, expense_repo};
+
+/// Past expenses needed in a category before any are flagged.
+const MIN_SAMPLE_SIZE: i64 = 10;
+
+/// How far back past expenses are considered.
+const HISTORY_MONTHS: u32 = 12;
+
+const STD_DEV_LIMIT: i64 = 3;
+
+/// Flag `expense` if it is far above the user's usual amount for its
+/// category, and record the flag.
+pub async fn check_expense(
+    pool: &PgPool,
+    user: &User,
+    expense: &Expense,
+) -> Result<Option<AnomalyFlag>, AppError> {
+    let since = expense.expense_date - Months::new(HISTORY_MONTHS);
+    let stats = expense_repo::category_amount_stats(pool, user.id, &expense.category, expense.id, since).await?;
+    let Some(amount) = exchange_rate_repo::convert(
+        pool,
+        expense.amount,
+        &expense.currency,
+        &user.base_currency,
+        expense.expense_date,
+    )
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let Some(flag) = evaluate(&expense.category, amount, &stats) else {
+        return Ok(None);
+    };
+    anomaly_repo::create(pool, expense, &flag).await?;
+    Ok(Some(flag))
+}
+
+/// Apply the anomaly rules to an amount. The standard deviation rule is
+/// checked first. The percentile rule also requires twice the mean, since
+/// one in twenty expenses is above the 95th percentile by definition.
+pub fn evaluate(category: &str, amount: Decimal, stats: &AmountStats) -> Option<AnomalyFlag> {
+    if stats.sample_size < MIN_SAMPLE_SIZE {
+        return None;
+    }
+    let (mean, std_dev, p95) = (stats.mean?, stats.std_dev?, stats.p95?);
+
+    let rule = if std_dev > Decimal::ZERO && amount > mean + std_dev * Decimal::from(STD_DEV_LIMIT) {
+        AnomalyRule::StdDev
+    } else if amount > p95 && amount >= mean * Decimal::TWO {
+        AnomalyRule::Percentile
+    } else {
+        return None;
+    };
+
+    let reason = match rule {
+        AnomalyRule::StdDev => format!(
+            "{} is more than {} standard deviations above your average {} expense of {}",
+            amount.round_dp(2),
+            STD_DEV_LIMIT,
+            category,
+            mean
+        ),
+        AnomalyRule::Percentile => format!(
+            "{} is higher than 95% of your {} expenses and over twice the average of {}",
+            amount.round_dp(2),
+            category,
+            mean
+        ),
+    };
+
+    Some(AnomalyFlag {
+        rule,
+        reason,
+        amount: amount.round_dp(2),
+        typical_amount: mean,
+        std_dev,
+        p95,
+        sample_size: stats.sample_size,
+    })
+}
+
+pub async fn list_anomalies(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: &AnomalyFilter,
+) -> Result<Vec<ExpenseAnomaly>, AppError> {
+    anomaly_repo::find_all(pool, user_id, filter).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(sample_size: i64, mean: i64, std_dev: i64, p95: i64) -> AmountStats {
+        AmountStats {
+            sample_size,
+            mean: Some(Decimal::from(mean)),
+            std_dev: Some(Decimal::from(std_dev)),
+            p95: Some(Decimal::from(p95)),
+        }
+    }
+
+    fn rule(amount: i64, stats: &AmountStats) -> Option<AnomalyRule> {
+        evaluate("food", Decimal::from(amount), stats).map(|f| f.rule)
+    }
+
+    #[test]
+    fn more_than_three_std_devs_above_mean() {
+        let stats = stats(20, 100, 20, 500);
+        assert_eq!(rule(161, &stats), Some(AnomalyRule::StdDev));
+        assert_eq!(rule(160, &stats), None);
+
+        let flag = evaluate("food", Decimal::from(161), &stats).unwrap();
+        assert_eq!(flag.typical_amount, Decimal::from(100));
+        assert_eq!(flag.sample_size, 20);
+        assert!(flag.reason.contains("3 standard deviations above your average food expense"));
+    }
+
+    #[test]
+    fn above_p95_and_twice_the_mean() {
+        // A wide spread keeps the standard deviation rule out of reach
+        let wide = stats(20, 100, 100, 180);
+        assert_eq!(rule(200, &wide), Some(AnomalyRule::Percentile));
+        // Above p95 but under twice the mean
+        assert_eq!(rule(199, &wide), None);
+        // Twice the mean but not above p95
+        assert_eq!(rule(200, &stats(20, 100, 100, 200)), None);
+    }
+
+    #[test]
+    fn std_dev_rule_is_checked_first() {
+        assert_eq!(rule(1000, &stats(20, 100, 20, 150)), Some(AnomalyRule::StdDev));
+    }
+
+    #[test]
+    fn needs_enough_history() {
+        assert_eq!(rule(1000, &stats(MIN_SAMPLE_SIZE - 1, 100, 20, 150)), None);
+        assert_eq!(rule(1000, &stats(MIN_SAMPLE_SIZE, 100, 20, 150)), Some(AnomalyRule::StdDev));
+
+        let empty = AmountStats { sample_size: 20, mean: None, std_dev: None, p95: None };
+        assert_eq!(rule(1000, &empty), None);
+    }
+
+    #[test]
+    fn zero_std_dev_uses_percentile_rule_only() {
+        // Every past expense was 100
+        let stats = stats(20, 100, 0, 100);
+        assert_eq!(rule(101, &stats), None);
+        assert_eq!(rule(200, &stats), Some(AnomalyRule::Percentile));
+    }
+}
@@ -0,0 +1,110 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::attachment::*;
+use crate::errors::AppError;
+use crate::infrastructure::attachment_repo;
+use crate::services::expense_service;
+use crate::services::storage_service::StorageService;
+
+/// Largest attachment accepted, which comfortably fits a phone photo or a PDF receipt.
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Accepted content types and the file extension stored for each.
+const ALLOWED_TYPES: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/webp", "webp"),
+    ("image/heic", "heic"),
+    ("application/pdf", "pdf"),
+];
+
+pub async fn upload_attachment(
+    pool: &PgPool,
+    storage: &StorageService,
+    expense_id: Uuid,
+    user_id: Uuid,
+    upload: AttachmentUpload,
+) -> Result<AttachmentLink, AppError> {
+    expense_service::get_expense(pool, expense_id, user_id).await?;
+
+    let ext = ALLOWED_TYPES
+        .iter()
+        .find(|(t, _)| *t == upload.content_type)
+        .map(|(_, ext)| *ext)
+        .ok_or_else(|| {
+            AppError::Validation(format!(
+                "Unsupported file type {}; upload a JPEG, PNG, WebP, HEIC or PDF",
+                upload.content_type
+            ))
+        })?;
+    if upload.data.is_empty() {
+        return Err(AppError::Validation("Uploaded file is empty".into()));
+    }
+    if upload.data.len() > MAX_ATTACHMENT_BYTES {
+        return Err(AppError::Validation(format!(
+            "Attachments can be at most {} MB",
+            MAX_ATTACHMENT_BYTES / (1024 * 1024)
+        )));
+    }
+
+    let s3_key = format!("receipts/{}/{}/{}.{}", user_id, expense_id, Uuid::new_v4(), ext);
+    let size_bytes = upload.data.len() as i64;
+    storage.put(&s3_key, upload.data, &upload.content_type).await?;
+
+    let attachment = match attachment_repo::create(
+        pool,
+        expense_id,
+        user_id,
+        &s3_key,
+        &upload.file_name,
+        &upload.content_type,
+        size_bytes,
+    )
+    .await
+    {
+        Ok(attachment) => attachment,
+        Err(e) => {
+            // The expense may have been deleted meanwhile; don't leave the file behind
+            storage.delete_all(&[s3_key]).await;
+            return Err(e);
+        }
+    };
+
+    let url = storage.presign_url(&attachment.s3_key).await?;
+    Ok(AttachmentLink { attachment, url })
+}
+
+pub async fn list_attachments(
+    pool: &PgPool,
+    storage: &StorageService,
+    expense_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<AttachmentLink>, AppError> {
+    expense_service::get_expense(pool, expense_id, user_id).await?;
+
+    let attachments = attachment_repo::find_by_expense(pool, expense_id, user_id).await?;
+    let mut links = Vec::with_capacity(attachments.len());
+    for attachment in attachments {
+        let url = storage.presign_url(&attachment.s3_key).await?;
+        links.push(AttachmentLink { attachment, url });
+    }
+    Ok(links)
+}
+
+pub async fn delete_attachment(
+    pool: &PgPool,
+    storage: &StorageService,
+    expense_id: Uuid,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<(), AppError> {
+    // A trashed expense keeps its attachments until it is restored or purged
+    expense_service::get_expense(pool, expense_id, user_id).await?;
+
+    let s3_key = attachment_repo::delete(pool, id, expense_id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Attachment not found".to_string()))?;
+    storage.delete_all(&[s3_key]).await;
+    Ok(())
+}
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::budget::*;
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::budget_repo;
+use crate::services::analytics_service;
+
+/// Upper bound on `carry_over_periods`, so a daily budget can look back a year at most.
+const MAX_CARRY_OVER_PERIODS: i32 = 366;
+
+/// Change the budget from the user's today onwards. Earlier days keep the
+/// setting that was in force for them.
+pub async fn set_budget(
+    pool: &PgPool,
+    user: &User,
+    input: &SetBudgetRequest,
+) -> Result<BudgetSetting, AppError> {
+    if input.amount < Decimal::ZERO {
+        return Err(AppError::Validation("Budget cannot be negative".into()));
+    }
+    match input.carry_over_cap {
+        Some(cap) if cap < Decimal::ZERO => {
+            return Err(AppError::Validation("carry_over_cap cannot be negative".into()));
+        }
+        None if input.carry_over == CarryOver::Capped => {
+            return Err(AppError::Validation(
+                "carry_over_cap is required for the capped rule".into(),
+            ));
+        }
+        _ => {}
+    }
+    if let Some(periods) = input.carry_over_periods {
+        if !(1..=MAX_CARRY_OVER_PERIODS).contains(&periods) {
+            return Err(AppError::Validation(format!(
+                "carry_over_periods must be between 1 and {}",
+                MAX_CARRY_OVER_PERIODS
+            )));
+        }
+    }
+    budget_repo::upsert(pool, user.id, input, user.today()).await
+}
+
+/// The user's budget changes, newest first.
+pub async fn history(pool: &PgPool, user_id: Uuid) -> Result<Vec<BudgetSetting>, AppError> {
+    let mut settings = budget_repo::history(pool, user_id).await?;
+    settings.reverse();
+    Ok(settings)
+}
+
+/// The budget for the period containing `date`: the period amount plus
+/// whatever the user's carry-over rule brings forward from earlier periods.
+pub async fn effective_budget(
+    pool: &PgPool,
+    user: &User,
+    date: NaiveDate,
+) -> Result<EffectiveBudget, AppError> {
+    let mut history = budget_repo::history(pool, user.id).await?;
+    history.retain(|s| s.effective_from <= date);
+    let categories = category_statuses(pool, user.id, date).await?;
+
+    // The setting in force on `date` decides the period and rules
+    let setting = history.last();
+    let (period, rule) = match setting {
+        Some(s) => rules(s)?,
+        None => Default::default(),
+    };
+    let (period_start, period_end) = period.bounds(date);
+    let budget = setting.map(|s| s.amount).unwrap_or(Decimal::ZERO);
+
+    if budget <= Decimal::ZERO {
+        return Ok(EffectiveBudget {
+            period,
+            period_start,
+            period_end,
+            budget: Decimal::ZERO,
+            carry_over: rule,
+            carried_over: Decimal::ZERO,
+            effective_budget: Decimal::ZERO,
+            spent: Decimal::ZERO,
+            remaining: Decimal::ZERO,
+            spent_today: Decimal::ZERO,
+            categories,
+        });
+    }
+
+    let lookback = setting
+        .and_then(|s| s.carry_over_periods)
+        .map(|n| n as u32)
+        .unwrap_or_else(|| period.default_carry_over_periods());
+    // Only periods that had a budget from their first day carry over
+    let first_budget_day = history[0].effective_from;
+    let mut previous = Vec::new();
+    let mut start = period_start;
+    if rule != CarryOver::None {
+        for _ in 0..lookback {
+            let bounds = period.bounds(start - Duration::days(1));
+            if bounds.0 < first_budget_day {
+                break;
+            }
+            start = bounds.0;
+            previous.push(bounds);
+        }
+        // Oldest first, so carry-over accumulates in the order it happened
+        previous.reverse();
+    }
+
+    let spending = budget_repo::daily_spending(
+        pool,
+        user.id,
+        &start.format("%Y-%m-%d").to_string(),
+        &date.format("%Y-%m-%d").to_string(),
+    )
+    .await?;
+
+    let mut by_period: HashMap<NaiveDate, Decimal> = HashMap::new();
+    let mut spent_today = Decimal::ZERO;
+    for (day, amount) in &spending {
+        let day: NaiveDate = day
+            .parse()
+            .map_err(|e| AppError::Internal(format!("Invalid spending date {}: {}", day, e)))?;
+        *by_period.entry(period.bounds(day).0).or_default() += *amount;
+        if day == date {
+            spent_today = *amount;
+        }
+    }
+
+    let mut previous_periods = Vec::with_capacity(previous.len());
+    for (start, end) in previous {
+        let spent = by_period.get(&start).copied().unwrap_or(Decimal::ZERO);
+        previous_periods.push((period_budget(&history, start, end)?, spent));
+    }
+    let cap = setting.and_then(|s| s.carry_over_cap);
+    let carried_over = carry_over(rule, cap, &previous_periods);
+
+    let spent = by_period.get(&period_start).copied().unwrap_or(Decimal::ZERO);
+    let effective = budget + carried_over;
+
+    Ok(EffectiveBudget {
+        period,
+        period_start,
+        period_end,
+        budget,
+        carry_over: rule,
+        carried_over,
+        effective_budget: effective,
+        spent,
+        remaining: effective - spent,
+        spent_today,
+        categories,
+    })
+}
+
+/// Total budget for the days `start..=end` under the settings in `history`
+/// (oldest first). Each day gets its share of the setting in force that day,
+/// so a mid-period change or a switch between periods is prorated. Days
+/// before the first setting have no budget.
+pub fn period_budget(
+    history: &[BudgetSetting],
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Decimal, AppError> {
+    let mut total = Decimal::ZERO;
+    let mut day = start;
+    while day <= end {
+        if let Some(setting) = history.iter().rev().find(|s| s.effective_from <= day) {
+            let (period, _) = rules(setting)?;
+            let (period_start, period_end) = period.bounds(day);
+            let days = (period_end - period_start).num_days() + 1;
+            total += setting.amount / Decimal::from(days);
+        }
+        day += Duration::days(1);
+    }
+    Ok(total.round_dp(2))
+}
+
+fn rules(setting: &BudgetSetting) -> Result<(BudgetPeriod, CarryOver), AppError> {
+    let period = setting.period.parse().map_err(AppError::Internal)?;
+    let rule = setting.carry_over.parse().map_err(AppError::Internal)?;
+    Ok((period, rule))
+}
+
+/// Carry-over into the current period, given the `(budget, spent)` of each
+/// earlier period, oldest first. The balance is rolled forward period by
+/// period, so a saving can absorb a later overspend before the rule decides
+/// what survives.
+pub fn carry_over(rule: CarryOver, cap: Option<Decimal>, periods: &[(Decimal, Decimal)]) -> Decimal {
+    periods.iter().fold(Decimal::ZERO, |balance, (budget, spent)| {
+        let balance = balance + budget - spent;
+        match rule {
+            CarryOver::Full => balance,
+            CarryOver::None => Decimal::ZERO,
+            CarryOver::Savings => balance.max(Decimal::ZERO),
+            CarryOver::Overspend => balance.min(Decimal::ZERO),
+            CarryOver::Capped => {
+                let cap = cap.unwrap_or(Decimal::ZERO);
+                balance.clamp(-cap, cap)
+            }
+        }
+    })
+}
+
+/// Spending per category from the start of `date`'s month through `date`.
+/// Category budgets and their alerts both measure spending this way.
+pub async fn month_category_spending(
+    pool: &PgPool,
+    user_id: Uuid,
+    date: NaiveDate,
+) -> Result<Vec<(String, Decimal)>, AppError> {
+    let (month_start, _) = analytics_service::month_bounds(date);
+    budget_repo::category_spending(pool, user_id, month_start, date).await
+}
+
+/// Month-to-date spending against each category's monthly limit.
+async fn category_statuses(
+    pool: &PgPool,
+    user_id: Uuid,
+    today: NaiveDate,
+) -> Result<Vec<CategoryBudgetStatus>, AppError> {
+    let budgets = budget_repo::list_category_budgets(pool, user_id).await?;
+    if budgets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let spending = month_category_spending(pool, user_id, today).await?;
+
+    Ok(budgets
+        .into_iter()
+        .map(|b| {
+            let spent = spending
+                .iter()
+                .find(|(c, _)| c == &b.category)
+                .map(|(_, s)| *s)
+                .unwrap_or(Decimal::ZERO);
+            let percent_used = if b.monthly_limit > Decimal::ZERO {
+                (spent * Decimal::ONE_HUNDRED / b.monthly_limit).round_dp(1)
+            } else {
+                Decimal::ZERO
+            };
+            CategoryBudgetStatus {
+                category: b.category,
+                monthly_limit: b.monthly_limit,
+                spent,
+                remaining: b.monthly_limit - spent,
+                percent_used,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(n: i64) -> Decimal {
+        Decimal::from(n)
+    }
+
+    /// `(budget, spent)` pairs for periods with the same budget.
+    fn against(budget: i64, spent: &[i64]) -> Vec<(Decimal, Decimal)> {
+        spent.iter().map(|s| (d(budget), d(*s))).collect()
+    }
+
+    /// Against a budget of 100 a period: saved 60, overspent 30, saved 10.
+    fn spending() -> Vec<(Decimal, Decimal)> {
+        against(100, &[40, 130, 90])
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn setting(amount: i64, period: BudgetPeriod, effective_from: NaiveDate) -> BudgetSetting {
+        BudgetSetting {
+            id: Uuid::nil(),
+            user_id: Uuid::nil(),
+            amount: d(amount),
+            period: period.to_string(),
+            carry_over: CarryOver::Full.to_string(),
+            carry_over_cap: None,
+            carry_over_periods: None,
+            effective_from,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn full_carries_savings_and_overspend() {
+        assert_eq!(carry_over(CarryOver::Full, None, &spending()), d(40));
+        assert_eq!(carry_over(CarryOver::Full, None, &against(100, &[150, 120])), d(-70));
+    }
+
+    #[test]
+    fn none_carries_nothing() {
+        assert_eq!(carry_over(CarryOver::None, None, &spending()), Decimal::ZERO);
+        assert_eq!(carry_over(CarryOver::None, None, &against(100, &[500])), Decimal::ZERO);
+    }
+
+    #[test]
+    fn savings_never_goes_negative() {
+        assert_eq!(carry_over(CarryOver::Savings, None, &spending()), d(40));
+        // Overspending with nothing saved is forgiven, and later savings still count
+        assert_eq!(carry_over(CarryOver::Savings, None, &against(100, &[300, 80])), d(20));
+    }
+
+    #[test]
+    fn overspend_never_goes_positive() {
+        // Earlier savings are dropped, so the later overspend counts in full
+        assert_eq!(carry_over(CarryOver::Overspend, None, &spending()), d(-20));
+        // Savings only pay down overspend that came before them
+        assert_eq!(carry_over(CarryOver::Overspend, None, &against(100, &[150, 80])), d(-30));
+        assert_eq!(carry_over(CarryOver::Overspend, None, &against(100, &[50, 150])), d(-50));
+    }
+
+    #[test]
+    fn capped_limits_both_directions() {
+        let cap = Some(d(25));
+        assert_eq!(carry_over(CarryOver::Capped, cap, &spending()), d(5));
+        assert_eq!(carry_over(CarryOver::Capped, cap, &against(100, &[200])), d(-25));
+        assert_eq!(carry_over(CarryOver::Capped, cap, &against(100, &[90])), d(10));
+        // The cap applies each period, so a large saving can't bank beyond it
+        assert_eq!(carry_over(CarryOver::Capped, cap, &against(100, &[0, 120])), d(5));
+    }
+
+    #[test]
+    fn no_history_carries_nothing() {
+        assert_eq!(carry_over(CarryOver::Full, None, &[]), Decimal::ZERO);
+    }
+
+    #[test]
+    fn carry_over_uses_each_periods_budget() {
+        // Budget raised from 100 to 200: 80 spent under each
+        let periods = [(d(100), d(80)), (d(200), d(80))];
+        assert_eq!(carry_over(CarryOver::Full, None, &periods), d(140));
+    }
+
+    #[test]
+    fn period_budget_uses_setting_in_force_each_day() {
+        let history = [
+            setting(100, BudgetPeriod::Daily, date(2, 1)),
+            setting(150, BudgetPeriod::Daily, date(2, 4)),
+        ];
+        // Before the first setting there is no budget
+        assert_eq!(period_budget(&history, date(1, 30), date(2, 1)).unwrap(), d(100));
+        assert_eq!(period_budget(&history, date(2, 2), date(2, 3)).unwrap(), d(200));
+        assert_eq!(period_budget(&history, date(2, 3), date(2, 5)).unwrap(), d(400));
+    }
+
+    #[test]
+    fn period_budget_prorates_across_periods() {
+        // A monthly budget of 2800 in February is 100 a day
+        let history = [
+            setting(2800, BudgetPeriod::Monthly, date(2, 1)),
+            setting(1400, BudgetPeriod::Weekly, date(2, 16)),
+        ];
+        assert_eq!(period_budget(&history, date(2, 1), date(2, 28)).unwrap(), d(1500 + 2600));
+        assert_eq!(period_budget(&history, date(2, 9), date(2, 15)).unwrap(), d(700));
+        assert_eq!(period_budget(&history, date(2, 16), date(2, 22)).unwrap(), d(1400));
+    }
+
+    #[test]
+    fn period_bounds() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).unwrap();
+        assert_eq!(BudgetPeriod::Daily.bounds(date), (date, date));
+        assert_eq!(
+            BudgetPeriod::Weekly.bounds(date),
+            (
+                NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 2, 22).unwrap()
+            )
+        );
+        assert_eq!(
+            BudgetPeriod::Monthly.bounds(date),
+            (
+                NaiveDate::from_ymd_opt(2026, 2, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 2, 28).unwrap()
+            )
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+/// Keywords that suggest a category, checked in order. More specific
+/// categories come first so that, for example, a user with a "fuel" category
+/// gets it for "petrol", while everyone else falls through to "travel".
+const CATEGORY_KEYWORDS: &[(&str, &[&str])] = &[
+    ("groceries", &[
+        "grocery", "groceries", "supermarket", "bigbasket", "blinkit", "zepto", "dmart",
+        "vegetables", "fruits",
+    ]),
+    ("fuel", &["fuel", "petrol", "diesel", "hpcl", "bpcl", "iocl", "shell"]),
+    ("rent", &["rent", "landlord"]),
+    ("kids", &["school", "tuition", "daycare", "toys"]),
+    ("health", &["pharmacy", "medical", "hospital", "clinic", "doctor", "apollo", "medicines"]),
+    ("food", &[
+        "food", "restaurant", "cafe", "coffee", "tea", "lunch", "dinner", "breakfast", "snacks",
+        "swiggy", "zomato", "pizza", "burger", "starbucks", "mcdonalds", "dominos", "kfc", "bakery",
+        "grocery", "groceries", "supermarket",
+    ]),
+    ("travel", &[
+        "travel", "uber", "ola", "rapido", "taxi", "cab", "auto", "metro", "train", "irctc",
+        "railway", "flight", "airline", "indigo", "bus", "parking", "toll", "petrol", "fuel", "diesel",
+    ]),
+    ("bills", &[
+        "bill", "bills", "electricity", "water", "internet", "broadband", "wifi", "airtel", "jio",
+        "vodafone", "recharge", "insurance", "emi", "utility", "rent",
+    ]),
+    ("entertainment", &[
+        "entertainment", "netflix", "spotify", "hotstar", "prime", "movie", "movies", "cinema",
+        "pvr", "inox", "bookmyshow", "concert", "game", "games",
+    ]),
+    ("shopping", &[
+        "shopping", "amazon", "flipkart", "myntra", "ajio", "mall", "store", "mart", "clothes",
+        "shoes",
+    ]),
+];
+
+/// Lowercased words of `text`, padded with spaces so whole words and
+/// phrases can be matched with `contains(" word ")`.
+fn word_string(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+/// Suggest one of the user's `categories` for free text such as a bank payee
+/// or a quick-add note. A category whose own name appears in the text wins;
+/// otherwise the keyword table is used. Returns `None` if nothing matched.
+pub fn suggest_category(text: &str, categories: &HashSet<String>) -> Option<String> {
+    let words = word_string(text);
+
+    let mut by_name: Vec<&String> = categories
+        .iter()
+        .filter(|c| words.contains(&format!(" {} ", word_string(c).trim())))
+        .collect();
+    // Prefer the longest name, e.g. "eating out" over "out"
+    by_name.sort_by_key(|c| std::cmp::Reverse(c.len()));
+    if let Some(category) = by_name.first() {
+        return Some((*category).clone());
+    }
+
+    CATEGORY_KEYWORDS
+        .iter()
+        .filter(|(category, _)| categories.contains(*category))
+        .find(|(_, keywords)| keywords.iter().any(|k| words.contains(&format!(" {} ", k))))
+        .map(|(category, _)| category.to_string())
+}
@@ -0,0 +1,79 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::category::*;
+use crate::errors::AppError;
+use crate::infrastructure::category_repo;
+
+pub async fn list_categories(pool: &PgPool, user_id: Uuid) -> Result<Vec<Category>, AppError> {
+    category_repo::find_all(pool, user_id).await
+}
+
+pub async fn create_category(
+    pool: &PgPool,
+    user_id: Uuid,
+    input: &CreateCategory,
+) -> Result<Category, AppError> {
+    let name = normalize_name(&input.name);
+    validate_name(&name).map_err(AppError::Validation)?;
+    if let Some(color) = &input.color {
+        validate_color(color).map_err(AppError::Validation)?;
+    }
+
+    category_repo::create(pool, user_id, &name, input)
+        .await?
+        .ok_or_else(|| AppError::Conflict(format!("Category '{}' already exists", name)))
+}
+
+pub async fn update_category(
+    pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+    input: &UpdateCategory,
+) -> Result<Category, AppError> {
+    let existing = category_repo::find_by_id(pool, id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Category not found".to_string()))?;
+
+    let name = input.name.as_deref().map(normalize_name);
+    if let Some(name) = &name {
+        validate_name(name).map_err(AppError::Validation)?;
+        if *name != existing.name && category_repo::exists(pool, user_id, name).await? {
+            return Err(AppError::Conflict(format!("Category '{}' already exists", name)));
+        }
+    }
+    if let Some(Some(color)) = &input.color {
+        validate_color(color).map_err(AppError::Validation)?;
+    }
+
+    category_repo::update(pool, id, user_id, name.as_deref(), input)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Category not found".to_string()))
+}
+
+pub async fn delete_category(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+    let category = category_repo::find_by_id(pool, id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Category not found".to_string()))?;
+
+    let in_use = category_repo::expense_count(pool, user_id, &category.name).await?;
+    if in_use > 0 {
+        return Err(AppError::Conflict(format!(
+            "Category '{}' is used by {} expense(s)",
+            category.name, in_use
+        )));
+    }
+
+    category_repo::delete(pool, id, user_id).await?;
+    Ok(())
+}
+
+/// Ensure `name` is one of the user's categories, matched the way names are
+/// stored. Returns the stored name.
+pub async fn ensure_exists(pool: &PgPool, user_id: Uuid, name: &str) -> Result<String, AppError> {
+    let normalized = normalize_name(name);
+    if !category_repo::exists(pool, user_id, &normalized).await? {
+        return Err(AppError::Validation(format!("Invalid category: {}", name)));
+    }
+    Ok(normalized)
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use crate::domain::currency::normalize_code;
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::exchange_rate_repo;
+
+/// The currency for a new expense: the given code, or the user's base currency.
+pub fn resolve(user: &User, currency: Option<&str>) -> Result<String, AppError> {
+    match currency {
+        Some(code) => normalize_code(code).map_err(AppError::Validation),
+        None => Ok(user.base_currency.clone()),
+    }
+}
+
+/// Fail unless amounts in `currency` on `date` can be converted to `base`.
+/// Checked when expenses are written so that summaries never meet an amount
+/// they cannot convert.
+pub async fn ensure_convertible(
+    pool: &PgPool,
+    currency: &str,
+    base: &str,
+    date: NaiveDate,
+) -> Result<(), AppError> {
+    if ConvertibilityCheck::new(base).is_convertible(pool, currency, date).await? {
+        Ok(())
+    } else {
+        Err(missing_rate(currency, base, date))
+    }
+}
+
+fn missing_rate(currency: &str, base: &str, date: NaiveDate) -> AppError {
+    AppError::Validation(format!(
+        "No exchange rate from {} to {} on or before {}",
+        currency, base, date
+    ))
+}
+
+/// Checks many (currency, date) pairs against `base` with one query per
+/// distinct currency.
+pub struct ConvertibilityCheck<'a> {
+    base: &'a str,
+    first_rate_dates: HashMap<String, Option<NaiveDate>>,
+}
+
+impl<'a> ConvertibilityCheck<'a> {
+    pub fn new(base: &'a str) -> Self {
+        Self {
+            base,
+            first_rate_dates: HashMap::new(),
+        }
+    }
+
+    pub async fn is_convertible(
+        &mut self,
+        pool: &PgPool,
+        currency: &str,
+        date: NaiveDate,
+    ) -> Result<bool, AppError> {
+        if currency == self.base {
+            return Ok(true);
+        }
+        if !self.first_rate_dates.contains_key(currency) {
+            let first = exchange_rate_repo::first_rate_date(pool, currency, self.base).await?;
+            self.first_rate_dates.insert(currency.to_string(), first);
+        }
+        Ok(matches!(self.first_rate_dates[currency], Some(first) if first <= date))
+    }
+}
+
+/// Validate a new base currency: every currency the user already has
+/// expenses in must be convertible to it from its first use.
+pub async fn validate_base_currency(
+    pool: &PgPool,
+    user: &User,
+    code: &str,
+) -> Result<String, AppError> {
+    let base = normalize_code(code).map_err(AppError::Validation)?;
+    if base == user.base_currency {
+        return Ok(base);
+    }
+    for (currency, first_used) in exchange_rate_repo::currencies_in_use(pool, user.id, &base).await? {
+        ensure_convertible(pool, &currency, &base, first_used).await?;
+    }
+    Ok(base)
+}
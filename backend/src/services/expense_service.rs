@@ -1,22 +1,90 @@
+use std::collections::HashSet;
+
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::config::Config;
+use crate::domain::anomaly::CreatedExpense;
+use crate::domain::category::normalize_name;
+use crate::domain::currency::normalize_code;
 use crate::domain::expense::*;
+use crate::domain::pagination::{decode_cursor, page_size, Page};
+use crate::domain::user::User;
 use crate::errors::AppError;
-use crate::infrastructure::expense_repo;
+use crate::infrastructure::{category_repo, expense_repo};
+use crate::services::{
+    anomaly_service, category_service, currency_service, notification_service, quick_add_parser,
+};
 
 pub async fn create_expense(
     pool: &PgPool,
-    user_id: Uuid,
+    config: &Config,
+    user: &User,
     input: &CreateExpense,
-) -> Result<Expense, AppError> {
-    let expense = expense_repo::create(pool, user_id, input).await?;
+) -> Result<CreatedExpense, AppError> {
+    let mut input = input.clone();
+    input.category = category_service::ensure_exists(pool, user.id, &input.category).await?;
+    input.tags = normalize_tags(&input.tags).map_err(AppError::Validation)?;
+
+    let date = input.expense_date.unwrap_or_else(|| user.today());
+    let currency = currency_service::resolve(user, input.currency.as_deref())?;
+    currency_service::ensure_convertible(pool, &currency, &user.base_currency, date).await?;
+    let expense = expense_repo::create(pool, user.id, &input, &currency, date).await?;
 
     // Auto-mark daily submission when expense is created
-    let date = input.expense_date.unwrap_or_else(|| chrono::Utc::now().date_naive());
-    let _ = expense_repo::mark_daily_submission(pool, user_id, date).await;
+    let _ = expense_repo::mark_daily_submission(pool, user.id, date).await;
 
-    Ok(expense)
+    // The expense is saved either way, so a failed check only loses the flag
+    let anomaly = anomaly_service::check_expense(pool, user, &expense)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!("Anomaly check failed for expense {}: {}", expense.id, e);
+            None
+        });
+
+    // Alert in the background if this pushes the category over its monthly
+    // limit, or if it is unusually large and the user opted in
+    let (pool, config, user) = (pool.clone(), config.clone(), user.clone());
+    let (created, flag) = (expense.clone(), anomaly.clone());
+    tokio::spawn(async move {
+        if let Err(e) = notification_service::check_category_budget(&pool, &config, &user, &created.category, created.expense_date).await {
+            tracing::error!("Category budget check failed for user {}: {}", user.id, e);
+        }
+        if let Some(flag) = flag.filter(|_| user.anomaly_alerts) {
+            if let Err(e) = notification_service::send_anomaly_alert(&config, &user, &flag).await {
+                tracing::error!("Anomaly alert failed for user {}: {}", user.id, e);
+            }
+        }
+    });
+
+    Ok(CreatedExpense {
+        expense,
+        is_anomaly: anomaly.is_some(),
+        anomaly,
+    })
+}
+
+/// Parse quick-add text into an expense, and save it if `commit` is set.
+pub async fn quick_add(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    input: &QuickAddRequest,
+) -> Result<QuickAddResult, AppError> {
+    let categories: HashSet<String> = category_repo::find_all(pool, user.id)
+        .await?
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    let (expense, confidence) = quick_add_parser::parse(&input.text, &categories, user.today())
+        .map_err(AppError::Validation)?;
+
+    let created = if input.commit {
+        Some(create_expense(pool, config, user, &expense).await?)
+    } else {
+        None
+    };
+    Ok(QuickAddResult { expense, confidence, created })
 }
 
 pub async fn get_expense(
@@ -33,26 +101,103 @@ pub async fn list_expenses(
     pool: &PgPool,
     user_id: Uuid,
     filter: &ExpenseFilter,
-) -> Result<Vec<Expense>, AppError> {
-    expense_repo::find_all(pool, user_id, filter).await
+) -> Result<Page<Expense>, AppError> {
+    let per_page = page_size(filter.per_page);
+    let before: Option<ExpenseKey> = filter
+        .cursor
+        .as_deref()
+        .map(decode_cursor)
+        .transpose()
+        .map_err(AppError::Validation)?;
+
+    let total = expense_repo::count(pool, user_id, filter).await?;
+    let rows = expense_repo::find_all(pool, user_id, filter, before, per_page + 1).await?;
+    Ok(Page::from_rows(rows, per_page, total, Expense::key))
+}
+
+/// Search notes and tags, with filters on tags, categories, amount and date.
+pub async fn search_expenses(
+    pool: &PgPool,
+    user_id: Uuid,
+    search: &ExpenseSearch,
+) -> Result<ExpenseSearchResult, AppError> {
+    let page = search.page.unwrap_or(1).max(1);
+    let per_page = search.per_page.unwrap_or(20).clamp(1, 100);
+    if let (Some(min), Some(max)) = (search.min_amount, search.max_amount) {
+        if min > max {
+            return Err(AppError::Validation("min_amount cannot be more than max_amount".into()));
+        }
+    }
+
+    let split = |list: &Option<String>| -> Vec<String> {
+        list.as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(str::to_string)
+            .collect()
+    };
+    let tags = normalize_tags(&split(&search.tags)).map_err(AppError::Validation)?;
+    let categories: Vec<String> = split(&search.categories)
+        .iter()
+        .map(|c| normalize_name(c))
+        .filter(|c| !c.is_empty())
+        .collect();
+    let criteria = ExpenseSearchCriteria {
+        q: search.q.as_deref().map(str::trim).filter(|q| !q.is_empty()).map(str::to_string),
+        tags: (!tags.is_empty()).then_some(tags),
+        categories: (!categories.is_empty()).then_some(categories),
+        min_amount: search.min_amount,
+        max_amount: search.max_amount,
+        start_date: search.start_date,
+        end_date: search.end_date,
+    };
+
+    let total = expense_repo::search_count(pool, user_id, &criteria).await?;
+    let expenses =
+        expense_repo::search(pool, user_id, &criteria, per_page, (page - 1) * per_page).await?;
+    Ok(ExpenseSearchResult { total, page, per_page, expenses })
 }
 
 pub async fn update_expense(
     pool: &PgPool,
+    config: &Config,
     id: Uuid,
-    user_id: Uuid,
+    user: &User,
     input: &UpdateExpense,
 ) -> Result<Expense, AppError> {
-    expense_repo::update(pool, id, user_id, input)
+    let mut input = input.clone();
+    if let Some(category) = &input.category {
+        input.category = Some(category_service::ensure_exists(pool, user.id, category).await?);
+    }
+    if let Some(code) = &input.currency {
+        input.currency = Some(normalize_code(code).map_err(AppError::Validation)?);
+    }
+    if let Some(tags) = &input.tags {
+        input.tags = Some(normalize_tags(tags).map_err(AppError::Validation)?);
+    }
+    // Moving an expense to another currency or date needs a rate for it
+    if input.currency.is_some() || input.expense_date.is_some() {
+        let existing = get_expense(pool, id, user.id).await?;
+        let currency = input.currency.as_deref().unwrap_or(&existing.currency);
+        let date = input.expense_date.unwrap_or(existing.expense_date);
+        currency_service::ensure_convertible(pool, currency, &user.base_currency, date).await?;
+    }
+
+    let expense = expense_repo::update(pool, id, user.id, &input)
         .await?
-        .ok_or_else(|| AppError::NotFound("Expense not found".to_string()))
+        .ok_or_else(|| AppError::NotFound("Expense not found".to_string()))?;
+    notification_service::spawn_category_budget_checks(
+        pool,
+        config,
+        user,
+        vec![(expense.category.clone(), expense.expense_date)],
+    );
+    Ok(expense)
 }
 
-pub async fn delete_expense(
-    pool: &PgPool,
-    id: Uuid,
-    user_id: Uuid,
-) -> Result<(), AppError> {
+/// Move an expense to the trash. Its attachments are kept until the trash
+/// is purged.
+pub async fn delete_expense(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
     if !expense_repo::delete(pool, id, user_id).await? {
         return Err(AppError::NotFound("Expense not found".to_string()));
     }
@@ -0,0 +1,132 @@
+use axum::body::Body;
+use futures::stream;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::expense::*;
+use crate::errors::AppError;
+use crate::infrastructure::expense_repo;
+
+/// Rows fetched per query while streaming an export.
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+const CSV_HEADERS: [&str; 9] = [
+    "id",
+    "expense_date",
+    "amount",
+    "currency",
+    "category",
+    "note",
+    "tags",
+    "created_at",
+    "updated_at",
+];
+
+struct ExportState {
+    pool: PgPool,
+    user_id: Uuid,
+    filter: ExpenseFilter,
+    format: ExportFormat,
+    after: Option<ExpenseKey>,
+    written: usize,
+    done: bool,
+}
+
+/// Stream every expense matching `filter` in the requested format. Rows are
+/// read in batches, so memory use does not grow with the size of the export.
+pub fn export_expenses(
+    pool: PgPool,
+    user_id: Uuid,
+    filter: ExpenseFilter,
+    format: ExportFormat,
+) -> Body {
+    let state = ExportState {
+        pool,
+        user_id,
+        filter,
+        format,
+        after: None,
+        written: 0,
+        done: false,
+    };
+
+    Body::from_stream(stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        let chunk = next_chunk(&mut state).await;
+        if chunk.is_err() {
+            state.done = true;
+        }
+        Some((chunk, state))
+    }))
+}
+
+async fn next_chunk(state: &mut ExportState) -> Result<Vec<u8>, AppError> {
+    let batch = expense_repo::find_batch(
+        &state.pool,
+        state.user_id,
+        &state.filter,
+        state.after,
+        EXPORT_BATCH_SIZE,
+    )
+    .await?;
+
+    let first = state.written == 0 && state.after.is_none();
+    let last = (batch.len() as i64) < EXPORT_BATCH_SIZE;
+    if let Some(e) = batch.last() {
+        state.after = Some(e.key());
+    }
+    state.done = last;
+
+    let rows: Vec<ExpenseExportRow> = batch.iter().map(ExpenseExportRow::from).collect();
+    let chunk = match state.format {
+        ExportFormat::Csv => csv_chunk(&rows, first)?,
+        ExportFormat::Json => json_chunk(&rows, first, last, state.written)?,
+    };
+    state.written += rows.len();
+    Ok(chunk)
+}
+
+fn csv_chunk(rows: &[ExpenseExportRow], with_headers: bool) -> Result<Vec<u8>, AppError> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+    if with_headers {
+        writer
+            .write_record(CSV_HEADERS)
+            .map_err(|e| AppError::Internal(format!("CSV write failed: {}", e)))?;
+    }
+    for row in rows {
+        writer
+            .serialize(row)
+            .map_err(|e| AppError::Internal(format!("CSV write failed: {}", e)))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(format!("CSV write failed: {}", e)))
+}
+
+/// Write rows as part of a single JSON array spread across chunks.
+fn json_chunk(
+    rows: &[ExpenseExportRow],
+    first: bool,
+    last: bool,
+    written: usize,
+) -> Result<Vec<u8>, AppError> {
+    let mut out = Vec::new();
+    if first {
+        out.push(b'[');
+    }
+    for (i, row) in rows.iter().enumerate() {
+        if written + i > 0 {
+            out.push(b',');
+        }
+        serde_json::to_writer(&mut out, row)
+            .map_err(|e| AppError::Internal(format!("JSON write failed: {}", e)))?;
+    }
+    if last {
+        out.push(b']');
+    }
+    Ok(out)
+}
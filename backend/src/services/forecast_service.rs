@@ -0,0 +1,241 @@
+use chrono::{Duration, NaiveDate};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+use crate::domain::analytics::{Confidence, Forecast};
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::budget_repo;
+use crate::services::analytics_service::month_bounds;
+use crate::services::budget_service;
+
+/// Days of history below which a forecast is always low confidence.
+const MIN_DAYS_FOR_CONFIDENCE: i64 = 3;
+
+/// Project the month-end spending total from the average daily spend so far
+/// this month, and when the monthly budget will run out at that pace.
+pub async fn month_forecast(
+    pool: &PgPool,
+    user: &User,
+    date: Option<NaiveDate>,
+) -> Result<Forecast, AppError> {
+    let as_of = date.unwrap_or_else(|| user.today());
+    let (month_start, month_end) = month_bounds(as_of);
+
+    let spending = budget_repo::daily_spending(
+        pool,
+        user.id,
+        &month_start.format("%Y-%m-%d").to_string(),
+        &as_of.format("%Y-%m-%d").to_string(),
+    )
+    .await?;
+
+    // One entry per day so far, including days without spending
+    let mut daily = Vec::new();
+    let mut day = month_start;
+    while day <= as_of {
+        let day_str = day.format("%Y-%m-%d").to_string();
+        let spent = spending
+            .iter()
+            .find(|(d, _)| d == &day_str)
+            .map(|(_, s)| *s)
+            .unwrap_or(Decimal::ZERO);
+        daily.push((day, spent));
+        day += Duration::days(1);
+    }
+
+    let days_in_month = (month_end - month_start).num_days() + 1;
+    let days_elapsed = daily.len() as i64;
+    let days_remaining = days_in_month - days_elapsed;
+    let projection = project(&daily, days_in_month);
+
+    let mut history = budget_repo::history(pool, user.id).await?;
+    history.retain(|s| s.effective_from <= as_of);
+    let monthly_budget = Some(budget_service::period_budget(&history, month_start, month_end)?)
+        .filter(|b| *b > Decimal::ZERO);
+    let budget_exhausted_on = monthly_budget.and_then(|budget| {
+        exhaustion_date(&daily, budget, projection.daily_average, month_end)
+    });
+
+    Ok(Forecast {
+        currency: user.base_currency.clone(),
+        month_start,
+        month_end,
+        as_of,
+        days_elapsed,
+        days_remaining,
+        spent_to_date: projection.spent_to_date,
+        daily_average: projection.daily_average.round_dp(2),
+        projected_total: projection.total.round_dp(2),
+        projected_low: projection.low.round_dp(2),
+        projected_high: projection.high.round_dp(2),
+        monthly_budget,
+        projected_remaining: monthly_budget.map(|b| (b - projection.total).round_dp(2)),
+        budget_exhausted_on,
+        confidence: projection.confidence,
+        confidence_score: Decimal::from_f64(projection.score).unwrap_or(Decimal::ZERO).round_dp(2),
+    })
+}
+
+/// The month-end total projected from the days so far.
+struct Projection {
+    spent_to_date: Decimal,
+    daily_average: Decimal,
+    total: Decimal,
+    low: Decimal,
+    high: Decimal,
+    confidence: Confidence,
+    score: f64,
+}
+
+/// Project the month-end total from `daily`, one entry per day of the month
+/// so far, at the average daily spend.
+fn project(daily: &[(NaiveDate, Decimal)], days_in_month: i64) -> Projection {
+    let days_elapsed = daily.len() as i64;
+    let days_remaining = days_in_month - days_elapsed;
+
+    let spent_to_date: Decimal = daily.iter().map(|(_, s)| *s).sum();
+    let daily_average = spent_to_date / Decimal::from(days_elapsed);
+    let total = spent_to_date + daily_average * Decimal::from(days_remaining);
+
+    // Spread of the daily average, for the interval and confidence
+    let amounts: Vec<f64> = daily.iter().map(|(_, s)| s.to_f64().unwrap_or(0.0)).collect();
+    let mean = daily_average.to_f64().unwrap_or(0.0);
+    let std_dev = if amounts.len() > 1 {
+        let variance = amounts.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / (amounts.len() - 1) as f64;
+        variance.sqrt()
+    } else {
+        0.0
+    };
+    let margin = Decimal::from_f64(1.96 * std_dev / (amounts.len() as f64).sqrt()).unwrap_or(Decimal::ZERO)
+        * Decimal::from(days_remaining);
+
+    let coverage = days_elapsed as f64 / days_in_month as f64;
+    let variation = if mean > 0.0 { std_dev / mean } else { 0.0 };
+    let score = if days_elapsed < MIN_DAYS_FOR_CONFIDENCE {
+        coverage.min(0.3)
+    } else {
+        0.5 * coverage + 0.5 / (1.0 + variation)
+    };
+    let confidence = match score {
+        s if s >= 0.7 => Confidence::High,
+        s if s >= 0.4 => Confidence::Medium,
+        _ => Confidence::Low,
+    };
+
+    Projection {
+        spent_to_date,
+        daily_average,
+        total,
+        low: (total - margin).max(spent_to_date),
+        high: total + margin,
+        confidence,
+        score,
+    }
+}
+
+/// The first day cumulative spending reaches `budget`: from history if it
+/// already has, otherwise projected forward at `pace` within the month.
+fn exhaustion_date(
+    daily: &[(NaiveDate, Decimal)],
+    budget: Decimal,
+    pace: Decimal,
+    month_end: NaiveDate,
+) -> Option<NaiveDate> {
+    let mut cumulative = Decimal::ZERO;
+    for (day, spent) in daily {
+        cumulative += spent;
+        if cumulative >= budget {
+            return Some(*day);
+        }
+    }
+
+    if pace <= Decimal::ZERO {
+        return None;
+    }
+    let (last_day, _) = daily.last()?;
+    let days = ((budget - cumulative) / pace).ceil().to_i64()?;
+    let date = *last_day + Duration::days(days);
+    (date <= month_end).then_some(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    /// One entry per day from the 1st, with the given amounts.
+    fn days(amounts: &[i64]) -> Vec<(NaiveDate, Decimal)> {
+        amounts.iter().enumerate().map(|(i, a)| (date(i as u32 + 1), Decimal::from(*a))).collect()
+    }
+
+    #[test]
+    fn budget_already_exhausted() {
+        let daily = days(&[40, 70, 10, 0]);
+        assert_eq!(exhaustion_date(&daily, dec("100"), dec("30"), date(31)), Some(date(2)));
+        // Reaching the budget exactly counts
+        assert_eq!(exhaustion_date(&daily, dec("110"), dec("30"), date(31)), Some(date(2)));
+    }
+
+    #[test]
+    fn budget_exhausted_later_in_month() {
+        // 100 of 300 spent by the 4th; 200 more at 25 a day takes 8 days
+        let daily = days(&[25, 25, 25, 25]);
+        assert_eq!(exhaustion_date(&daily, dec("300"), dec("25"), date(31)), Some(date(12)));
+        // Part of a day rounds up
+        assert_eq!(exhaustion_date(&daily, dec("310"), dec("25"), date(31)), Some(date(13)));
+    }
+
+    #[test]
+    fn budget_never_exhausted() {
+        let daily = days(&[10, 10, 10]);
+        // Runs out after the month ends
+        assert_eq!(exhaustion_date(&daily, dec("1000"), dec("10"), date(31)), None);
+        // Nothing spent, so never at this pace
+        assert_eq!(exhaustion_date(&days(&[0, 0]), dec("100"), Decimal::ZERO, date(31)), None);
+    }
+
+    #[test]
+    fn projects_at_daily_average() {
+        let projection = project(&days(&[10, 30, 20, 20]), 31);
+        assert_eq!(projection.spent_to_date, dec("80"));
+        assert_eq!(projection.daily_average, dec("20"));
+        assert_eq!(projection.total, dec("620"));
+        assert!(projection.low < projection.total && projection.total < projection.high);
+        assert!(projection.low >= projection.spent_to_date);
+    }
+
+    #[test]
+    fn steady_spending_has_no_interval() {
+        let projection = project(&days(&[20; 10]), 30);
+        assert_eq!(projection.total, dec("600"));
+        assert_eq!(projection.low, projection.total);
+        assert_eq!(projection.high, projection.total);
+    }
+
+    #[test]
+    fn under_three_days_is_low_confidence() {
+        // Even perfectly steady spending
+        for amounts in [&[20][..], &[20, 20][..]] {
+            let projection = project(&days(amounts), 28);
+            assert_eq!(projection.confidence, Confidence::Low);
+            assert!(projection.score <= 0.3);
+        }
+        assert_ne!(project(&days(&[20, 20, 20]), 28).confidence, Confidence::Low);
+    }
+
+    #[test]
+    fn confidence_grows_with_coverage_and_steadiness() {
+        assert_eq!(project(&days(&[20; 25]), 30).confidence, Confidence::High);
+        assert_eq!(project(&days(&[0, 0, 0, 0, 200]), 30).confidence, Confidence::Low);
+    }
+}
@@ -0,0 +1,423 @@
+use chrono::{Duration, NaiveDate};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::goal::*;
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::{goal_repo, user_repo};
+use crate::services::budget_service;
+
+pub async fn create_goal(
+    pool: &PgPool,
+    user: &User,
+    input: &CreateGoal,
+) -> Result<GoalProgress, AppError> {
+    validate_name(&input.name)?;
+    validate_target(input.target_amount)?;
+    let today = user.today();
+    validate_deadline(input.deadline, today)?;
+
+    let mut tx = pool.begin().await?;
+    validate_auto_contribute(&mut tx, user.id, None, input.auto_contribute_percent).await?;
+    let goal = goal_repo::create(&mut *tx, user.id, input, today).await?;
+    tx.commit().await?;
+    Ok(progress(goal, Decimal::ZERO, today))
+}
+
+pub async fn list_goals(pool: &PgPool, user: &User) -> Result<Vec<GoalProgress>, AppError> {
+    let goals = goal_repo::find_all(pool, user.id).await?;
+    let saved = goal_repo::saved_totals(pool, user.id).await?;
+    let today = user.today();
+
+    Ok(goals
+        .into_iter()
+        .map(|goal| {
+            let total = saved
+                .iter()
+                .find(|(id, _)| *id == goal.id)
+                .map(|(_, s)| *s)
+                .unwrap_or(Decimal::ZERO);
+            progress(goal, total, today)
+        })
+        .collect())
+}
+
+pub async fn get_goal(pool: &PgPool, id: Uuid, user: &User) -> Result<GoalProgress, AppError> {
+    let goal = find_goal(pool, id, user.id).await?;
+    let saved = goal_repo::saved_total(pool, goal.id).await?;
+    Ok(progress(goal, saved, user.today()))
+}
+
+pub async fn update_goal(
+    pool: &PgPool,
+    id: Uuid,
+    user: &User,
+    input: &UpdateGoal,
+) -> Result<GoalProgress, AppError> {
+    if let Some(name) = &input.name {
+        validate_name(name)?;
+    }
+    if let Some(target) = input.target_amount {
+        validate_target(target)?;
+    }
+    if let Some(deadline) = input.deadline {
+        validate_deadline(deadline, user.today())?;
+    }
+
+    let mut tx = pool.begin().await?;
+    if let Some(percent) = input.auto_contribute_percent {
+        validate_auto_contribute(&mut tx, user.id, Some(id), percent).await?;
+    }
+    goal_repo::update(&mut *tx, id, user.id, input)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Goal not found".to_string()))?;
+    tx.commit().await?;
+    if input.target_amount.is_some() {
+        goal_repo::refresh_achieved(pool, id, user.today()).await?;
+    }
+    get_goal(pool, id, user).await
+}
+
+pub async fn delete_goal(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+    if !goal_repo::delete(pool, id, user_id).await? {
+        return Err(AppError::NotFound("Goal not found".to_string()));
+    }
+    Ok(())
+}
+
+pub async fn add_contribution(
+    pool: &PgPool,
+    goal_id: Uuid,
+    user: &User,
+    input: &CreateContribution,
+) -> Result<GoalContribution, AppError> {
+    if input.amount <= Decimal::ZERO {
+        return Err(AppError::Validation("Amount must be positive".into()));
+    }
+    let today = user.today();
+    let contributed_on = input.contributed_on.unwrap_or(today);
+    if contributed_on > today {
+        return Err(AppError::Validation("contributed_on cannot be in the future".into()));
+    }
+    find_goal(pool, goal_id, user.id).await?;
+
+    let mut tx = pool.begin().await?;
+    let contribution = goal_repo::add_contribution(
+        &mut *tx,
+        goal_id,
+        user.id,
+        input,
+        ContributionSource::Manual,
+        None,
+        contributed_on,
+    )
+    .await?
+    .ok_or_else(|| AppError::Internal("Manual contribution was not recorded".into()))?;
+    goal_repo::refresh_achieved(&mut *tx, goal_id, contributed_on).await?;
+    tx.commit().await?;
+    Ok(contribution)
+}
+
+pub async fn list_contributions(
+    pool: &PgPool,
+    goal_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<GoalContribution>, AppError> {
+    find_goal(pool, goal_id, user_id).await?;
+    goal_repo::find_contributions(pool, goal_id, user_id).await
+}
+
+pub async fn delete_contribution(
+    pool: &PgPool,
+    goal_id: Uuid,
+    id: Uuid,
+    user: &User,
+) -> Result<(), AppError> {
+    if !goal_repo::delete_contribution(pool, id, goal_id, user.id).await? {
+        return Err(AppError::NotFound("Contribution not found".to_string()));
+    }
+    goal_repo::refresh_achieved(pool, goal_id, user.today()).await
+}
+
+/// Save each user's unspent budget from the period that ended yesterday into
+/// their automatic goals. Safe to run repeatedly: a goal gets at most one
+/// automatic contribution per budget period.
+pub async fn contribute_unspent_budgets(pool: &PgPool) -> Result<(), AppError> {
+    for user_id in goal_repo::find_auto_contribute_user_ids(pool).await? {
+        if let Err(e) = contribute_unspent_budget(pool, user_id).await {
+            tracing::error!("Failed to save unspent budget for user {}: {}", user_id, e);
+        }
+    }
+    Ok(())
+}
+
+async fn contribute_unspent_budget(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+    let user = user_repo::find_by_id(pool, user_id)
+        .await?
+        .ok_or_else(|| AppError::Internal("Goal owner not found".into()))?;
+    let yesterday = user.today() - Duration::days(1);
+
+    let budget = budget_service::effective_budget(pool, &user, yesterday).await?;
+    if budget.period_end != yesterday {
+        return Ok(());
+    }
+    // Only this period's own savings: carried-over savings were already
+    // offered to the goals in the period they were made.
+    let unspent = (budget.budget - budget.spent).min(budget.remaining);
+    if unspent <= Decimal::ZERO {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+    for goal in goal_repo::lock_auto_contribute_goals(&mut *tx, user.id, yesterday).await? {
+        let share = (unspent * Decimal::from(goal.auto_contribute_percent) / Decimal::ONE_HUNDRED)
+            .round_dp(2);
+        let saved = goal_repo::saved_total(&mut *tx, goal.id).await?;
+        let amount = share.min(goal.target_amount - saved);
+        if amount <= Decimal::ZERO {
+            continue;
+        }
+
+        let contribution = CreateContribution {
+            amount,
+            note: Some(format!("Unspent {} budget", budget.period)),
+            contributed_on: None,
+        };
+        let added = goal_repo::add_contribution(
+            &mut *tx,
+            goal.id,
+            user.id,
+            &contribution,
+            ContributionSource::Auto,
+            Some(budget.period_start),
+            yesterday,
+        )
+        .await?;
+        if added.is_some() {
+            goal_repo::refresh_achieved(&mut *tx, goal.id, yesterday).await?;
+            tracing::info!("Saved {} of unspent budget into goal {}", amount, goal.id);
+        }
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn find_goal(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<SavingsGoal, AppError> {
+    goal_repo::find_by_id(pool, id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Goal not found".to_string()))
+}
+
+fn validate_name(name: &str) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(AppError::Validation("Goal name must be 1-100 characters".into()));
+    }
+    Ok(())
+}
+
+fn validate_target(target: Decimal) -> Result<(), AppError> {
+    if target <= Decimal::ZERO {
+        return Err(AppError::Validation("target_amount must be positive".into()));
+    }
+    Ok(())
+}
+
+/// Automatic goals split the unspent budget, so their shares can't add up
+/// to more than all of it. The check holds a lock on the user until `tx`
+/// ends, so the goal must be written in the same transaction.
+async fn validate_auto_contribute(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    goal_id: Option<Uuid>,
+    percent: i32,
+) -> Result<(), AppError> {
+    if !(0..=100).contains(&percent) {
+        return Err(AppError::Validation("auto_contribute_percent must be between 0 and 100".into()));
+    }
+    if percent == 0 {
+        return Ok(());
+    }
+    goal_repo::lock_auto_contribute(&mut **tx, user_id).await?;
+    let others = goal_repo::auto_contribute_total(&mut **tx, user_id, goal_id).await?;
+    if others + percent as i64 > 100 {
+        return Err(AppError::Validation(format!(
+            "Automatic goals can share at most 100% of the unspent budget; {}% is already assigned",
+            others
+        )));
+    }
+    Ok(())
+}
+
+/// Goals start on the day they are created, so a deadline from today on is
+/// never before the start date.
+fn validate_deadline(deadline: Option<NaiveDate>, today: NaiveDate) -> Result<(), AppError> {
+    if deadline.is_some_and(|d| d < today) {
+        return Err(AppError::Validation("deadline cannot be in the past".into()));
+    }
+    Ok(())
+}
+
+fn progress(goal: SavingsGoal, saved: Decimal, today: NaiveDate) -> GoalProgress {
+    let remaining = (goal.target_amount - saved).max(Decimal::ZERO);
+    let achieved = remaining.is_zero();
+    let percent_complete = (saved * Decimal::ONE_HUNDRED / goal.target_amount).round_dp(1);
+
+    let days_elapsed = ((today - goal.start_date).num_days() + 1).max(1);
+    let current_daily_rate = (saved / Decimal::from(days_elapsed)).round_dp(2);
+    let projected_completion = if achieved {
+        goal.achieved_on.or(Some(today))
+    } else if current_daily_rate > Decimal::ZERO {
+        (remaining / current_daily_rate)
+            .ceil()
+            .to_i64()
+            .map(|days| today + Duration::days(days))
+    } else {
+        None
+    };
+
+    let days_left = goal.deadline.map(|d| ((d - today).num_days() + 1).max(0));
+    let required_daily_rate = days_left.map(|days| match days {
+        _ if achieved => Decimal::ZERO,
+        0 => remaining,
+        days => (remaining / Decimal::from(days)).round_dp(2),
+    });
+
+    // On track when savings keep up with an even schedule from the start date
+    // to the deadline, counting the days before today.
+    let on_track = goal.deadline.map(|deadline| {
+        if achieved {
+            return true;
+        }
+        if today > deadline {
+            return false;
+        }
+        let total_days = (deadline - goal.start_date).num_days() + 1;
+        let days_before_today = (today - goal.start_date).num_days().max(0);
+        saved * Decimal::from(total_days) >= goal.target_amount * Decimal::from(days_before_today)
+    });
+
+    GoalProgress {
+        goal,
+        saved,
+        remaining,
+        percent_complete,
+        days_left,
+        required_daily_rate,
+        current_daily_rate,
+        projected_completion,
+        on_track,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, m, d).unwrap()
+    }
+
+    fn dec(n: i64) -> Decimal {
+        Decimal::from(n)
+    }
+
+    /// A goal of 1000 started on Jan 1.
+    fn goal(deadline: Option<NaiveDate>, achieved_on: Option<NaiveDate>) -> SavingsGoal {
+        SavingsGoal {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "Trip".into(),
+            target_amount: dec(1000),
+            start_date: date(1, 1),
+            deadline,
+            auto_contribute_percent: 0,
+            achieved_on,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn without_deadline() {
+        // 300 saved over Jan 1-10
+        let p = progress(goal(None, None), dec(300), date(1, 10));
+        assert_eq!(p.remaining, dec(700));
+        assert_eq!(p.percent_complete, dec(30));
+        assert_eq!(p.current_daily_rate, dec(30));
+        // 700 more at 30 a day takes 24 days
+        assert_eq!(p.projected_completion, Some(date(2, 3)));
+        assert_eq!(p.days_left, None);
+        assert_eq!(p.required_daily_rate, None);
+        assert_eq!(p.on_track, None);
+    }
+
+    #[test]
+    fn on_and_off_track() {
+        // Jan 1-Apr 10 is 100 days, so 10 a day keeps pace
+        let deadline = Some(date(4, 10));
+        let today = date(1, 21);
+        let on = progress(goal(deadline, None), dec(200), today);
+        assert_eq!(on.on_track, Some(true));
+        assert_eq!(on.days_left, Some(80));
+        assert_eq!(on.required_daily_rate, Some(dec(10)));
+
+        let off = progress(goal(deadline, None), dec(199), today);
+        assert_eq!(off.on_track, Some(false));
+        assert_eq!(off.required_daily_rate, Some("10.01".parse().unwrap()));
+    }
+
+    #[test]
+    fn deadline_day_needs_the_rest_today() {
+        let p = progress(goal(Some(date(1, 31)), None), dec(900), date(1, 31));
+        assert_eq!(p.days_left, Some(1));
+        assert_eq!(p.required_daily_rate, Some(dec(100)));
+    }
+
+    #[test]
+    fn past_deadline() {
+        let p = progress(goal(Some(date(1, 31)), None), dec(900), date(2, 5));
+        assert_eq!(p.days_left, Some(0));
+        assert_eq!(p.required_daily_rate, Some(dec(100)));
+        assert_eq!(p.on_track, Some(false));
+    }
+
+    #[test]
+    fn achieved() {
+        let p = progress(goal(Some(date(1, 31)), Some(date(1, 20))), dec(1200), date(2, 5));
+        assert_eq!(p.remaining, Decimal::ZERO);
+        assert_eq!(p.percent_complete, dec(120));
+        assert_eq!(p.projected_completion, Some(date(1, 20)));
+        assert_eq!(p.required_daily_rate, Some(Decimal::ZERO));
+        assert_eq!(p.on_track, Some(true));
+    }
+
+    #[test]
+    fn nothing_saved() {
+        let p = progress(goal(Some(date(3, 1)), None), Decimal::ZERO, date(1, 1));
+        assert_eq!(p.current_daily_rate, Decimal::ZERO);
+        assert_eq!(p.projected_completion, None);
+        // Nothing was due before today
+        assert_eq!(p.on_track, Some(true));
+    }
+
+    #[test]
+    fn deadlines_from_today_on() {
+        let today = date(3, 1);
+        assert!(validate_deadline(None, today).is_ok());
+        assert!(validate_deadline(Some(today), today).is_ok());
+        assert!(validate_deadline(Some(date(2, 28)), today).is_err());
+    }
+
+    #[test]
+    fn null_deadline_clears_it() {
+        let parse = |json| serde_json::from_str::<UpdateGoal>(json).unwrap().deadline;
+        assert_eq!(parse(r#"{"name": "Trip"}"#), None);
+        assert_eq!(parse(r#"{"deadline": null}"#), Some(None));
+        assert_eq!(parse(r#"{"deadline": "2026-03-01"}"#), Some(Some(date(3, 1))));
+    }
+}
@@ -0,0 +1,510 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::domain::category::normalize_name;
+use crate::domain::currency::normalize_code;
+use crate::domain::expense::*;
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::{category_repo, expense_repo, imported_transaction_repo};
+use crate::services::currency_service::ConvertibilityCheck;
+use crate::services::{categorizer, notification_service, statement_parser};
+
+const MAX_IMPORT_ROWS: usize = 10_000;
+
+/// Accepted date formats, tried in order.
+const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y", "%Y/%m/%d"];
+
+/// A parsed input row, before category and duplicate checks.
+pub struct DraftRow {
+    pub line: usize,
+    /// The bank's transaction id, for rows read from a statement.
+    pub fitid: Option<String>,
+    pub expense: Result<CreateExpense, Vec<String>>,
+}
+
+struct Columns {
+    date: usize,
+    amount: usize,
+    category: usize,
+    note: Option<usize>,
+    currency: Option<usize>,
+    tags: Option<usize>,
+}
+
+pub async fn import_csv(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    data: &[u8],
+    query: &ImportQuery,
+) -> Result<ImportReport, AppError> {
+    let drafts = parse_csv(data)?;
+    import_drafts(pool, config, user, drafts, query).await
+}
+
+/// Import the debits of an OFX or QIF bank statement. Each debit becomes an
+/// expense with a category suggested from its payee and memo, falling back to
+/// "other". Credits are skipped. A row's `line` is its position among the
+/// statement's debits.
+pub async fn import_statement(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    data: &[u8],
+    statement: &StatementImportQuery,
+    query: &ImportQuery,
+) -> Result<ImportReport, AppError> {
+    let data = std::str::from_utf8(data)
+        .map_err(|_| AppError::Validation("Statement must be UTF-8 text".into()))?;
+    let format = statement
+        .format
+        .or_else(|| statement_parser::detect_format(data))
+        .ok_or_else(|| AppError::Validation("Unrecognized statement format".into()))?;
+    let transactions = statement_parser::parse(data, format, statement.day_first)
+        .map_err(|e| AppError::Validation(format!("Invalid statement: {}", e)))?;
+
+    let debits: Vec<_> = transactions.into_iter().filter(|t| t.is_debit()).collect();
+    if debits.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::Validation(format!(
+            "Import is limited to {} rows",
+            MAX_IMPORT_ROWS
+        )));
+    }
+
+    let categories: HashSet<String> = category_repo::find_all(pool, user.id)
+        .await?
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+
+    let drafts = debits
+        .into_iter()
+        .enumerate()
+        .map(|(i, txn)| {
+            let note = txn.note();
+            let category = note
+                .as_deref()
+                .and_then(|n| categorizer::suggest_category(n, &categories))
+                .unwrap_or_else(|| "other".to_string());
+            DraftRow {
+                line: i + 1,
+                expense: Ok(CreateExpense {
+                    amount: -txn.amount,
+                    currency: txn.currency,
+                    category,
+                    note,
+                    tags: Vec::new(),
+                    expense_date: Some(txn.date),
+                }),
+                fitid: Some(txn.fitid),
+            }
+        })
+        .collect();
+
+    import_drafts(pool, config, user, drafts, query).await
+}
+
+/// Parse a CSV with a header row. Columns are matched by name,
+/// case-insensitively: `date` (or `expense_date`), `amount`, `category`, and
+/// optionally `note` (or `notes`, `description`, `memo`), `currency` and
+/// `tags` (separated by commas, semicolons or spaces).
+pub fn parse_csv(data: &[u8]) -> Result<Vec<DraftRow>, AppError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let headers = reader
+        .headers()
+        .map_err(|e| AppError::Validation(format!("Invalid CSV header: {}", e)))?
+        .clone();
+    let required = |names: &[&str]| {
+        find_column(&headers, names)
+            .ok_or_else(|| AppError::Validation(format!("Missing CSV column: {}", names[0])))
+    };
+    let columns = Columns {
+        date: required(&["date", "expense_date"])?,
+        amount: required(&["amount"])?,
+        category: required(&["category"])?,
+        note: find_column(&headers, &["note", "notes", "description", "memo"]),
+        currency: find_column(&headers, &["currency"]),
+        tags: find_column(&headers, &["tags"]),
+    };
+
+    let mut rows = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        if i >= MAX_IMPORT_ROWS {
+            return Err(AppError::Validation(format!(
+                "Import is limited to {} rows",
+                MAX_IMPORT_ROWS
+            )));
+        }
+        // Header is line 1
+        let line = i + 2;
+        let expense = match record {
+            Ok(record) => parse_record(&record, &columns),
+            Err(e) => Err(vec![format!("Malformed row: {}", e)]),
+        };
+        rows.push(DraftRow {
+            line,
+            fitid: None,
+            expense,
+        });
+    }
+    Ok(rows)
+}
+
+fn find_column(headers: &csv::StringRecord, names: &[&str]) -> Option<usize> {
+    headers
+        .iter()
+        .position(|h| names.contains(&h.trim().to_lowercase().as_str()))
+}
+
+fn parse_record(record: &csv::StringRecord, columns: &Columns) -> Result<CreateExpense, Vec<String>> {
+    let field = |i: usize| record.get(i).unwrap_or("").trim();
+    let mut errors = Vec::new();
+
+    let expense_date = match parse_date(field(columns.date)) {
+        Ok(date) => Some(date),
+        Err(e) => {
+            errors.push(e);
+            None
+        }
+    };
+    let amount = match parse_amount(field(columns.amount)) {
+        Ok(amount) => amount,
+        Err(e) => {
+            errors.push(e);
+            Decimal::ZERO
+        }
+    };
+    let category = normalize_name(field(columns.category));
+    if category.is_empty() {
+        errors.push("Category is required".to_string());
+    }
+    let note = columns
+        .note
+        .map(field)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string);
+    let currency = columns
+        .currency
+        .map(field)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string);
+    let tags: Vec<String> = columns
+        .tags
+        .map(field)
+        .unwrap_or("")
+        .split([',', ';', ' '])
+        .map(str::to_string)
+        .collect();
+    let tags = normalize_tags(&tags).unwrap_or_else(|e| {
+        errors.push(e);
+        Vec::new()
+    });
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(CreateExpense {
+        amount,
+        currency,
+        category,
+        note,
+        tags,
+        expense_date,
+    })
+}
+
+pub fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .ok_or_else(|| format!("Invalid date: '{}'", value))
+}
+
+/// Parse an amount, ignoring thousands separators and common currency symbols.
+pub fn parse_amount(value: &str) -> Result<Decimal, String> {
+    let cleaned: String = value
+        .chars()
+        .filter(|c| !matches!(c, ',' | ' ' | '₹' | '$' | '€' | '£'))
+        .collect();
+    let amount = Decimal::from_str(&cleaned).map_err(|_| format!("Invalid amount: '{}'", value))?;
+    if amount < Decimal::ZERO {
+        return Err(format!("Amount cannot be negative: '{}'", value));
+    }
+    Ok(amount)
+}
+
+/// Rows with the same date, amount and note (ignoring case and surrounding
+/// whitespace) are treated as likely duplicates.
+fn duplicate_key(date: NaiveDate, amount: Decimal, note: Option<&str>) -> (NaiveDate, Decimal, String) {
+    (
+        date,
+        amount.normalize(),
+        note.map(|n| n.trim().to_lowercase()).unwrap_or_default(),
+    )
+}
+
+/// Validate drafts against the user's categories, flag likely duplicates of
+/// existing expenses or earlier rows as well as bank transactions that were
+/// already imported, and unless this is a dry run insert the
+/// accepted rows in a single transaction. Nothing is inserted if any row is
+/// invalid.
+pub async fn import_drafts(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    drafts: Vec<DraftRow>,
+    query: &ImportQuery,
+) -> Result<ImportReport, AppError> {
+    let categories: HashSet<String> = category_repo::find_all(pool, user.id)
+        .await?
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+
+    let today = user.today();
+    let mut rows: Vec<ImportRowResult> = drafts
+        .into_iter()
+        .map(|draft| match draft.expense {
+            Ok(expense) if categories.contains(&expense.category) => ImportRowResult {
+                line: draft.line,
+                status: ImportRowStatus::Valid,
+                errors: Vec::new(),
+                fitid: draft.fitid,
+                expense: Some(expense),
+            },
+            Ok(expense) => ImportRowResult {
+                line: draft.line,
+                status: ImportRowStatus::Invalid,
+                errors: vec![format!("Invalid category: {}", expense.category)],
+                fitid: draft.fitid,
+                expense: Some(expense),
+            },
+            Err(errors) => ImportRowResult {
+                line: draft.line,
+                status: ImportRowStatus::Invalid,
+                errors,
+                fitid: draft.fitid,
+                expense: None,
+            },
+        })
+        .collect();
+
+    // Every currency needs a rate to the base currency on the row's date
+    let mut rates = ConvertibilityCheck::new(&user.base_currency);
+    for row in rows.iter_mut().filter(|r| r.status == ImportRowStatus::Valid) {
+        let Some(expense) = row.expense.as_mut() else { continue };
+        let date = expense.expense_date.unwrap_or(today);
+        let currency = match &expense.currency {
+            Some(code) => normalize_code(code),
+            None => Ok(user.base_currency.clone()),
+        };
+        let error = match currency {
+            Ok(currency) if rates.is_convertible(pool, &currency, date).await? => {
+                expense.currency = Some(currency);
+                continue;
+            }
+            Ok(currency) => format!(
+                "No exchange rate from {} to {} on or before {}",
+                currency, user.base_currency, date
+            ),
+            Err(e) => e,
+        };
+        row.status = ImportRowStatus::Invalid;
+        row.errors.push(error);
+    }
+
+    // Bank transactions seen in an earlier import, or earlier in this one
+    let fitids: Vec<String> = rows.iter().filter_map(|r| r.fitid.clone()).collect();
+    if !fitids.is_empty() {
+        let mut seen: HashSet<String> =
+            imported_transaction_repo::find_existing(pool, user.id, &fitids)
+                .await?
+                .into_iter()
+                .collect();
+        for row in rows.iter_mut() {
+            let Some(fitid) = &row.fitid else { continue };
+            if !seen.insert(fitid.clone()) {
+                row.status = ImportRowStatus::AlreadyImported;
+                row.errors = vec!["Transaction was already imported".to_string()];
+            }
+        }
+    }
+
+    let dates = || {
+        rows.iter()
+            .filter(|r| r.status == ImportRowStatus::Valid)
+            .filter_map(|r| r.expense.as_ref()?.expense_date)
+    };
+    if let (Some(start), Some(end)) = (dates().min(), dates().max()) {
+        let mut seen: HashSet<_> = expense_repo::duplicate_keys(pool, user.id, start, end)
+            .await?
+            .into_iter()
+            .map(|(date, amount, note)| duplicate_key(date, amount, note.as_deref()))
+            .collect();
+        for row in rows.iter_mut().filter(|r| r.status == ImportRowStatus::Valid) {
+            let Some(expense) = &row.expense else { continue };
+            let Some(date) = expense.expense_date else { continue };
+            if !seen.insert(duplicate_key(date, expense.amount, expense.note.as_deref())) {
+                row.status = ImportRowStatus::Duplicate;
+                row.errors.push("Likely duplicate of an existing expense".to_string());
+            }
+        }
+    }
+
+    let count = |status| rows.iter().filter(|r| r.status == status).count();
+    let (valid_rows, invalid_rows, duplicate_rows, already_imported_rows) = (
+        count(ImportRowStatus::Valid),
+        count(ImportRowStatus::Invalid),
+        count(ImportRowStatus::Duplicate),
+        count(ImportRowStatus::AlreadyImported),
+    );
+
+    let committed = !query.dry_run && invalid_rows == 0;
+    let mut inserted = 0;
+    let mut written = Vec::new();
+    if committed {
+        let mut tx = pool.begin().await?;
+        for row in &rows {
+            let accepted = row.status == ImportRowStatus::Valid
+                || (row.status == ImportRowStatus::Duplicate && query.include_duplicates);
+            let Some(expense) = row.expense.as_ref().filter(|_| accepted) else {
+                continue;
+            };
+            let date = expense.expense_date.unwrap_or(today);
+            let currency = expense.currency.as_deref().unwrap_or(&user.base_currency);
+            let created = expense_repo::create(&mut *tx, user.id, expense, currency, date).await?;
+            written.push((created.category.clone(), created.expense_date));
+            if let Some(fitid) = &row.fitid {
+                imported_transaction_repo::record(&mut *tx, user.id, fitid, created.id).await?;
+            }
+            inserted += 1;
+        }
+        tx.commit().await?;
+    }
+    notification_service::spawn_category_budget_checks(pool, config, user, written);
+
+    Ok(ImportReport {
+        dry_run: query.dry_run,
+        committed,
+        total_rows: rows.len(),
+        valid_rows,
+        invalid_rows,
+        duplicate_rows,
+        already_imported_rows,
+        inserted,
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn expenses(rows: &[DraftRow]) -> Vec<&CreateExpense> {
+        rows.iter().map(|r| r.expense.as_ref().unwrap()).collect()
+    }
+
+    #[test]
+    fn parses_csv_with_header_aliases() {
+        let rows = parse_csv(include_bytes!("../../tests/fixtures/expenses.csv")).unwrap();
+        assert_eq!(rows.iter().map(|r| r.line).collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+        assert!(rows.iter().all(|r| r.fitid.is_none()));
+
+        let expenses = expenses(&rows);
+        assert_eq!(expenses[0].expense_date, Some(date(2026, 1, 5)));
+        assert_eq!(expenses[0].amount, dec("1250.00"));
+        assert_eq!(expenses[0].category, "food");
+        assert_eq!(expenses[0].note.as_deref(), Some("Team lunch"));
+        assert_eq!(expenses[0].currency.as_deref(), Some("INR"));
+        assert_eq!(expenses[0].tags, vec!["work", "team"]);
+
+        assert_eq!(expenses[1].amount, dec("80"));
+        assert_eq!(expenses[1].currency, None);
+        assert!(expenses[1].tags.is_empty());
+        assert_eq!(expenses[2].category, "shopping");
+        assert_eq!(expenses[2].note, None);
+    }
+
+    #[test]
+    fn note_column_aliases() {
+        for name in ["note", "Notes", "memo", "DESCRIPTION"] {
+            let csv = format!("date,amount,category,{}\n2026-01-05,10,food,Coffee\n", name);
+            let rows = parse_csv(csv.as_bytes()).unwrap();
+            assert_eq!(expenses(&rows)[0].note.as_deref(), Some("Coffee"), "{}", name);
+        }
+    }
+
+    #[test]
+    fn missing_required_column() {
+        let err = parse_csv(b"date,category\n2026-01-05,food\n").err().unwrap();
+        assert!(matches!(err, AppError::Validation(m) if m == "Missing CSV column: amount"));
+    }
+
+    #[test]
+    fn each_date_format() {
+        for value in ["2026-01-31", "31/01/2026", "31-01-2026", "2026/01/31"] {
+            assert_eq!(parse_date(value), Ok(date(2026, 1, 31)), "{}", value);
+        }
+        assert!(parse_date("01/31/2026").is_err());
+        assert!(parse_date("Jan 31 2026").is_err());
+        assert!(parse_date("").is_err());
+    }
+
+    #[test]
+    fn amounts() {
+        assert_eq!(parse_amount("1,234.50"), Ok(dec("1234.50")));
+        assert_eq!(parse_amount("€ 9.99"), Ok(dec("9.99")));
+        assert_eq!(parse_amount("£0"), Ok(Decimal::ZERO));
+        assert_eq!(parse_amount("abc"), Err("Invalid amount: 'abc'".to_string()));
+        assert_eq!(parse_amount(""), Err("Invalid amount: ''".to_string()));
+        assert_eq!(parse_amount("-5"), Err("Amount cannot be negative: '-5'".to_string()));
+    }
+
+    #[test]
+    fn collects_every_error_in_a_row() {
+        let csv = "date,amount,category\n2026-13-01,ten,\n2026-01-05,10,food\n";
+        let rows = parse_csv(csv.as_bytes()).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0].expense.as_ref().err().unwrap(),
+            &vec![
+                "Invalid date: '2026-13-01'".to_string(),
+                "Invalid amount: 'ten'".to_string(),
+                "Category is required".to_string(),
+            ]
+        );
+        assert!(rows[1].expense.is_ok());
+    }
+
+    #[test]
+    fn duplicate_key_ignores_note_case_and_amount_scale() {
+        let day = date(2026, 1, 5);
+        assert_eq!(
+            duplicate_key(day, dec("10.50"), Some(" Coffee ")),
+            duplicate_key(day, dec("10.5"), Some("coffee")),
+        );
+        assert_eq!(duplicate_key(day, dec("10"), None), duplicate_key(day, dec("10.00"), Some("")));
+        assert_ne!(
+            duplicate_key(day, dec("10"), Some("coffee")),
+            duplicate_key(day, dec("10"), Some("tea")),
+        );
+    }
+}
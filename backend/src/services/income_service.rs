@@ -0,0 +1,73 @@
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::currency::normalize_code;
+use crate::domain::income::*;
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::income_repo;
+use crate::services::currency_service;
+
+pub async fn create_income(
+    pool: &PgPool,
+    user: &User,
+    input: &CreateIncome,
+) -> Result<Income, AppError> {
+    if input.amount < Decimal::ZERO {
+        return Err(AppError::Validation("Amount cannot be negative".into()));
+    }
+
+    let date = input.income_date.unwrap_or_else(|| user.today());
+    let currency = currency_service::resolve(user, input.currency.as_deref())?;
+    currency_service::ensure_convertible(pool, &currency, &user.base_currency, date).await?;
+    income_repo::create(pool, user.id, input, &currency, date).await
+}
+
+pub async fn get_income(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<Income, AppError> {
+    income_repo::find_by_id(pool, id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Income not found".to_string()))
+}
+
+pub async fn list_incomes(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: &IncomeFilter,
+) -> Result<Vec<Income>, AppError> {
+    income_repo::find_all(pool, user_id, filter).await
+}
+
+pub async fn update_income(
+    pool: &PgPool,
+    id: Uuid,
+    user: &User,
+    input: &UpdateIncome,
+) -> Result<Income, AppError> {
+    if input.amount.is_some_and(|a| a < Decimal::ZERO) {
+        return Err(AppError::Validation("Amount cannot be negative".into()));
+    }
+
+    let mut input = input.clone();
+    if let Some(code) = &input.currency {
+        input.currency = Some(normalize_code(code).map_err(AppError::Validation)?);
+    }
+    // Moving an income to another currency or date needs a rate for it
+    if input.currency.is_some() || input.income_date.is_some() {
+        let existing = get_income(pool, id, user.id).await?;
+        let currency = input.currency.as_deref().unwrap_or(&existing.currency);
+        let date = input.income_date.unwrap_or(existing.income_date);
+        currency_service::ensure_convertible(pool, currency, &user.base_currency, date).await?;
+    }
+
+    income_repo::update(pool, id, user.id, &input)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Income not found".to_string()))
+}
+
+pub async fn delete_income(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+    if !income_repo::delete(pool, id, user_id).await? {
+        return Err(AppError::NotFound("Income not found".to_string()));
+    }
+    Ok(())
+}
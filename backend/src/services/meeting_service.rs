@@ -1,27 +1,25 @@
-use aws_sdk_s3::primitives::ByteStream;
 use aws_sdk_transcribe::types::{LanguageCode, Media, MediaFormat};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::errors::AppError;
 use crate::infrastructure::meeting_note_repo;
+use crate::services::storage_service::StorageService;
 
 #[derive(Clone)]
 pub struct MeetingService {
-    s3_client: aws_sdk_s3::Client,
+    storage: StorageService,
     transcribe_client: aws_sdk_transcribe::Client,
     translate_client: aws_sdk_translate::Client,
-    bucket: String,
     region: String,
 }
 
 impl MeetingService {
-    pub async fn new(bucket: String, region: String) -> Self {
+    pub async fn new(storage: StorageService, region: String) -> Self {
         let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-        let s3_client = aws_sdk_s3::Client::new(&config);
         let transcribe_client = aws_sdk_transcribe::Client::new(&config);
         let translate_client = aws_sdk_translate::Client::new(&config);
-        Self { s3_client, transcribe_client, translate_client, bucket, region }
+        Self { storage, transcribe_client, translate_client, region }
     }
 
     pub async fn upload_audio(
@@ -36,25 +34,17 @@ impl MeetingService {
         let s3_key = format!("meetings/{}/{}.{}", user_id, note_id, ext);
         let audio_url = format!(
             "https://{}.s3.{}.amazonaws.com/{}",
-            self.bucket, self.region, s3_key
+            self.bucket(), self.region, s3_key
         );
 
-        self.s3_client
-            .put_object()
-            .bucket(&self.bucket)
-            .key(&s3_key)
-            .body(ByteStream::from(audio_data))
-            .content_type(content_type)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("S3 upload failed: {}", e)))?;
+        self.storage.put(&s3_key, audio_data, content_type).await?;
 
         meeting_note_repo::update_audio(pool, note_id, user_id, &s3_key, &audio_url, "transcribing")
             .await?;
 
         // Start transcription with automatic language identification
         let job_name = format!("lily-meeting-{}", note_id);
-        let media_uri = format!("s3://{}/{}", self.bucket, s3_key);
+        let media_uri = format!("s3://{}/{}", self.bucket(), s3_key);
         let media_format = if ext == "m4a" { MediaFormat::Mp4 } else { MediaFormat::Mp3 };
 
         let result = self.transcribe_client
@@ -71,7 +61,7 @@ impl MeetingService {
                     .build(),
             )
             .media_format(media_format)
-            .output_bucket_name(&self.bucket)
+            .output_bucket_name(self.bucket())
             .output_key(format!("transcripts/{}.json", note_id))
             .send()
             .await;
@@ -119,9 +109,9 @@ impl MeetingService {
 
         if status == "Completed" {
             let transcript_key = format!("transcripts/{}.json", note_id);
-            match self.s3_client
+            match self.storage.client()
                 .get_object()
-                .bucket(&self.bucket)
+                .bucket(self.bucket())
                 .key(&transcript_key)
                 .send()
                 .await
@@ -193,27 +183,19 @@ impl MeetingService {
     }
 
     pub fn bucket(&self) -> &str {
-        &self.bucket
+        self.storage.bucket()
     }
 
     pub fn region(&self) -> &str {
         &self.region
     }
 
-    pub async fn presign_url(&self, s3_key: &str) -> Result<String, AppError> {
-        use aws_sdk_s3::presigning::PresigningConfig;
-        use std::time::Duration;
-
-        let presigned = self.s3_client
-            .get_object()
-            .bucket(&self.bucket)
-            .key(s3_key)
-            .presigned(PresigningConfig::expires_in(Duration::from_secs(3600))
-                .map_err(|e| AppError::Internal(format!("Presign config error: {}", e)))?)
-            .await
-            .map_err(|e| AppError::Internal(format!("Presign error: {}", e)))?;
+    pub fn storage(&self) -> &StorageService {
+        &self.storage
+    }
 
-        Ok(presigned.uri().to_string())
+    pub async fn presign_url(&self, s3_key: &str) -> Result<String, AppError> {
+        self.storage.presign_url(s3_key).await
     }
 }
 
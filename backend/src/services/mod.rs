@@ -4,3 +4,23 @@ pub mod analytics_service;
 pub mod notification_service;
 pub mod daily_check_service;
 pub mod meeting_service;
+pub mod category_service;
+pub mod recurring_expense_service;
+pub mod export_service;
+pub mod import_service;
+pub mod statement_parser;
+pub mod categorizer;
+pub mod currency_service;
+pub mod income_service;
+pub mod forecast_service;
+pub mod anomaly_service;
+pub mod budget_service;
+pub mod goal_service;
+pub mod storage_service;
+pub mod attachment_service;
+pub mod ocr;
+pub mod receipt_parser;
+pub mod receipt_service;
+pub mod quick_add_parser;
+pub mod sync_service;
+pub mod trash_service;
@@ -1,10 +1,19 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::config::Config;
+use crate::domain::anomaly::AnomalyFlag;
+use crate::domain::user::User;
 use crate::errors::AppError;
-use crate::infrastructure::user_repo;
-use crate::services::daily_check_service;
+use crate::infrastructure::{budget_repo, expense_repo, reminder_repo, user_repo};
+use crate::services::{analytics_service, budget_service};
+
+/// Percentages of a category's monthly limit that trigger an alert.
+const CATEGORY_ALERT_THRESHOLDS: [i32; 2] = [80, 100];
 
 pub async fn register_device(
     pool: &PgPool,
@@ -36,6 +45,21 @@ pub async fn register_device(
 }
 
 pub async fn send_reminder(config: &Config, endpoint_arn: &str) -> Result<(), AppError> {
+    send_push(
+        config,
+        endpoint_arn,
+        "Budget Tracker",
+        "Don't forget to log your expenses today!",
+    )
+    .await
+}
+
+pub async fn send_push(
+    config: &Config,
+    endpoint_arn: &str,
+    title: &str,
+    body: &str,
+) -> Result<(), AppError> {
     if config.sns_platform_application_arn.is_none() {
         return Ok(());
     }
@@ -46,8 +70,8 @@ pub async fn send_reminder(config: &Config, endpoint_arn: &str) -> Result<(), Ap
     let message = serde_json::json!({
         "GCM": serde_json::json!({
             "notification": {
-                "title": "Budget Tracker",
-                "body": "Don't forget to log your expenses today!"
+                "title": title,
+                "body": body
             }
         }).to_string()
     });
@@ -67,18 +91,152 @@ pub async fn send_reminder(config: &Config, endpoint_arn: &str) -> Result<(), Ap
     Ok(())
 }
 
+/// Send the daily reminder to every user whose local `notification_time` has
+/// passed today and who has not yet submitted the day. Each reminder is
+/// recorded in `daily_reminders`, so a user is reminded at most once per local
+/// day; a failed send is retried a few times with growing gaps.
 pub async fn send_daily_reminders(pool: &PgPool, config: &Config) -> Result<(), AppError> {
-    let user_ids = daily_check_service::get_users_without_submission(pool).await?;
-
-    for user_id in user_ids {
-        if let Ok(Some(user)) = user_repo::find_by_id(pool, user_id).await {
-            if let Some(ref arn) = user.sns_endpoint_arn {
-                if let Err(e) = send_reminder(config, arn).await {
-                    tracing::error!("Failed to send reminder to user {}: {}", user_id, e);
-                }
-            }
+    let now = Utc::now();
+    let users = user_repo::find_all_with_sns(pool).await?;
+
+    for user in users {
+        if let Err(e) = send_daily_reminder(pool, config, &user, now).await {
+            tracing::error!("Failed to remind user {}: {}", user.id, e);
         }
     }
 
     Ok(())
 }
+
+async fn send_daily_reminder(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let local = user.local_time(now);
+    if local.time() < user.notification_time {
+        return Ok(());
+    }
+
+    let today = local.date_naive();
+    if expense_repo::check_daily_submission(pool, user.id, today).await? {
+        return Ok(());
+    }
+
+    let Some(ref arn) = user.sns_endpoint_arn else {
+        return Ok(());
+    };
+    let Some(reminder) = reminder_repo::claim(pool, user.id, today).await? else {
+        return Ok(());
+    };
+
+    match send_reminder(config, arn).await {
+        Ok(()) => reminder_repo::mark_sent(pool, reminder.id).await,
+        Err(e) => {
+            tracing::error!("Failed to send reminder to user {}: {}", user.id, e);
+            reminder_repo::mark_failed(pool, reminder.id, &e.to_string()).await
+        }
+    }
+}
+
+/// Push an alert for an expense flagged as unusually large.
+pub async fn send_anomaly_alert(
+    config: &Config,
+    user: &User,
+    flag: &AnomalyFlag,
+) -> Result<(), AppError> {
+    let Some(arn) = &user.sns_endpoint_arn else {
+        return Ok(());
+    };
+    send_push(config, arn, "Unusual expense", &flag.reason).await
+}
+
+/// Push an alert when an expense written on `date` takes its category past
+/// 80% or 100% of the monthly limit. Each threshold is alerted at most once a
+/// month. Only the current month is checked, with spending counted up to
+/// today as `GET /budget` shows it, so back-dated and future-dated expenses
+/// don't alert.
+pub async fn check_category_budget(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    category: &str,
+    date: NaiveDate,
+) -> Result<(), AppError> {
+    let today = user.today();
+    let (month_start, _) = analytics_service::month_bounds(today);
+    if !(month_start..=today).contains(&date) {
+        return Ok(());
+    }
+    let Some(budget) = budget_repo::get_category_budget(pool, user.id, category).await? else {
+        return Ok(());
+    };
+    if budget.monthly_limit <= Decimal::ZERO {
+        return Ok(());
+    }
+
+    // Limits are in the base currency, as is the spending total
+    let spent = budget_service::month_category_spending(pool, user.id, today)
+        .await?
+        .into_iter()
+        .find(|(c, _)| c == category)
+        .map(|(_, s)| s)
+        .unwrap_or(Decimal::ZERO);
+
+    let mut crossed = None;
+    for threshold in CATEGORY_ALERT_THRESHOLDS {
+        let limit = budget.monthly_limit * Decimal::from(threshold) / Decimal::ONE_HUNDRED;
+        if spent >= limit
+            && budget_repo::record_category_alert(pool, user.id, category, month_start, threshold).await?
+        {
+            crossed = Some(threshold);
+        }
+    }
+
+    let (Some(threshold), Some(arn)) = (crossed, &user.sns_endpoint_arn) else {
+        return Ok(());
+    };
+    let body = if threshold >= 100 {
+        format!(
+            "You've gone over your {} budget of {} for this month.",
+            category, budget.monthly_limit
+        )
+    } else {
+        format!(
+            "You've used {}% of your {} budget for this month.",
+            threshold, category
+        )
+    };
+    send_push(config, arn, "Budget alert", &body).await
+}
+
+/// Run `check_category_budget` in the background for expenses written by an
+/// update, a sync, an import or a recurring run. `written` holds each
+/// expense's category and date; each category with an expense this month is
+/// checked once.
+pub fn spawn_category_budget_checks(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    written: Vec<(String, NaiveDate)>,
+) {
+    let today = user.today();
+    let (month_start, _) = analytics_service::month_bounds(today);
+    let mut seen = HashSet::new();
+    let written: Vec<_> = written
+        .into_iter()
+        .filter(|(c, date)| (month_start..=today).contains(date) && seen.insert(c.clone()))
+        .collect();
+    if written.is_empty() {
+        return;
+    }
+    let (pool, config, user) = (pool.clone(), config.clone(), user.clone());
+    tokio::spawn(async move {
+        for (category, date) in written {
+            if let Err(e) = check_category_budget(&pool, &config, &user, &category, date).await {
+                tracing::error!("Category budget check failed for user {}: {}", user.id, e);
+            }
+        }
+    });
+}
@@ -0,0 +1,138 @@
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::errors::AppError;
+
+/// Turns an image into text. Implementations are blocking; call them from
+/// `tokio::task::spawn_blocking`.
+pub trait OcrEngine: Send + Sync {
+    fn recognize(&self, image: &[u8]) -> Result<String, AppError>;
+}
+
+/// How long tesseract may take on one image before it is killed.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runs the local `tesseract` command, so no image leaves the server.
+pub struct TesseractOcr {
+    binary: String,
+    languages: String,
+}
+
+impl TesseractOcr {
+    /// `languages` is tesseract's `-l` value, e.g. `eng` or `eng+hin`.
+    pub fn new(binary: String, languages: String) -> Self {
+        Self { binary, languages }
+    }
+}
+
+impl OcrEngine for TesseractOcr {
+    fn recognize(&self, image: &[u8]) -> Result<String, AppError> {
+        // Page segmentation mode 4 reads a single column of lines, which suits receipts
+        let mut child = Command::new(&self.binary)
+            .args(["stdin", "stdout", "-l", &self.languages, "--psm", "4"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| AppError::Internal(format!("Failed to run {}: {}", self.binary, e)))?;
+        let (Some(mut stdin), Some(mut stdout), Some(mut stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            return Err(AppError::Internal("Tesseract pipes unavailable".into()));
+        };
+
+        // The pipes are serviced on their own threads so a stuck process can
+        // be killed; killing it closes the pipes and ends the threads
+        let (status, written, out, err) = thread::scope(|scope| {
+            let writer = scope.spawn(move || stdin.write_all(image));
+            let out = scope.spawn(move || read_all(&mut stdout));
+            let err = scope.spawn(move || read_all(&mut stderr));
+            let status = wait_or_kill(&mut child, TIMEOUT);
+            (status, writer.join(), out.join(), err.join())
+        });
+
+        let Some(status) = status.map_err(|e| AppError::Internal(format!("Tesseract failed: {}", e)))? else {
+            return Err(AppError::Validation("Reading the image took too long".into()));
+        };
+        let joined = "Tesseract pipe thread panicked";
+        let (out, err) = (
+            out.map_err(|_| AppError::Internal(joined.into()))?,
+            err.map_err(|_| AppError::Internal(joined.into()))?,
+        );
+        if !status.success() {
+            return Err(AppError::Validation(format!(
+                "Could not read the image: {}",
+                String::from_utf8_lossy(&err).trim()
+            )));
+        }
+        written
+            .map_err(|_| AppError::Internal(joined.into()))?
+            .map_err(|e| AppError::Internal(format!("Failed to send image to tesseract: {}", e)))?;
+        Ok(String::from_utf8_lossy(&out).into_owned())
+    }
+}
+
+fn read_all(pipe: &mut impl Read) -> Vec<u8> {
+    let mut buf = Vec::new();
+    // A read error only loses output; the exit status reports the failure
+    let _ = pipe.read_to_end(&mut buf);
+    buf
+}
+
+/// Wait for the child to exit, killing it after `timeout`. `None` means it
+/// was killed.
+fn wait_or_kill(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// The tesseract tests need it installed, with its English data:
+///
+/// ```sh
+/// TESSERACT_PATH=tesseract cargo test -- --ignored
+/// ```
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tesseract() -> TesseractOcr {
+        let binary = std::env::var("TESSERACT_PATH").unwrap_or_else(|_| "tesseract".to_string());
+        TesseractOcr::new(binary, "eng".to_string())
+    }
+
+    #[test]
+    #[ignore = "needs tesseract installed"]
+    fn reads_a_receipt_image() {
+        let text = tesseract()
+            .recognize(include_bytes!("../../tests/fixtures/receipts/fuel.png"))
+            .unwrap();
+        assert!(text.contains("FUELS"), "unexpected text: {}", text);
+        assert!(text.contains("2000.00"), "unexpected text: {}", text);
+    }
+
+    #[test]
+    #[ignore = "needs tesseract installed"]
+    fn rejects_an_unreadable_image() {
+        assert!(matches!(tesseract().recognize(b"not an image"), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn kills_a_process_that_runs_too_long() {
+        let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+        let started = Instant::now();
+        assert!(wait_or_kill(&mut child, Duration::from_millis(100)).unwrap().is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,420 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use chrono::{Datelike, Days, Duration, NaiveDate, Weekday};
+use rust_decimal::Decimal;
+
+use crate::domain::expense::{normalize_tags, Confidence, CreateExpense, QuickAddConfidence};
+use crate::services::categorizer;
+
+/// Words that mark the number next to them as the amount: "120 rs", "inr 500".
+const CURRENCY_WORDS: &[&str] = &["₹", "rs", "rs.", "inr", "rupee", "rupees", "bucks"];
+
+/// Furthest back "n days ago" may reach.
+const MAX_DAYS_AGO: u32 = 3650;
+
+/// Words that only join the others together: "spent 120 on coffee".
+const FILLER_WORDS: &[&str] = &["spent", "paid", "for", "on", "at", "of", "a", "an", "the"];
+
+struct Token<'a> {
+    text: &'a str,
+    /// Lowercased, for matching.
+    word: String,
+    used: bool,
+}
+
+/// Parse quick-add text such as "120 coffee yesterday" or "uber 350 travel"
+/// into an expense. The amount is required. The date defaults to `today`,
+/// and the category to "other" when neither one of `categories` nor a
+/// keyword for one appears. Words starting with '#' become tags, and
+/// whatever is left over becomes the note.
+pub fn parse(
+    text: &str,
+    categories: &HashSet<String>,
+    today: NaiveDate,
+) -> Result<(CreateExpense, QuickAddConfidence), String> {
+    let mut tokens: Vec<Token> = text
+        .split_whitespace()
+        .map(|t| t.trim_end_matches([',', ';', '!', '?']))
+        .filter(|t| !t.is_empty())
+        .map(|text| Token { text, word: text.to_lowercase(), used: false })
+        .collect();
+
+    let tags: Vec<String> = tokens
+        .iter_mut()
+        .filter(|t| t.word.len() > 1 && t.word.starts_with('#'))
+        .map(|t| {
+            t.used = true;
+            t.word.clone()
+        })
+        .collect();
+    let tags = normalize_tags(&tags)?;
+
+    // Dates go first so "3 days ago" or "12 feb" aren't read as amounts
+    let date = take_date(&mut tokens, today);
+    let (amount, amount_confidence) =
+        take_amount(&mut tokens).ok_or_else(|| "Couldn't find an amount in the text".to_string())?;
+    let (category, category_confidence) = take_category(&mut tokens, categories);
+
+    let note: Vec<&str> = tokens
+        .iter()
+        .filter(|t| !t.used && !FILLER_WORDS.contains(&t.word.as_str()))
+        .map(|t| t.text)
+        .collect();
+
+    let expense = CreateExpense {
+        amount,
+        currency: None,
+        category,
+        note: (!note.is_empty()).then(|| note.join(" ")),
+        tags,
+        expense_date: Some(date.unwrap_or(today)),
+    };
+    let confidence = QuickAddConfidence {
+        amount: amount_confidence,
+        category: category_confidence,
+        date: if date.is_some() { Confidence::High } else { Confidence::Medium },
+    };
+    Ok((expense, confidence))
+}
+
+/// The first date in the text, either relative ("yesterday", "3 days ago",
+/// "last friday") or written out ("12/02", "12 feb", "2026-02-12").
+fn take_date(tokens: &mut [Token], today: NaiveDate) -> Option<NaiveDate> {
+    let words: Vec<String> = tokens.iter().map(|t| t.word.clone()).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+    for i in 0..words.len() {
+        let rest = &words[i..];
+        let found = match rest {
+            ["day", "before", "yesterday", ..] => Some((today - Duration::days(2), 3)),
+            ["today" | "tonight", ..] => Some((today, 1)),
+            ["yesterday", ..] => Some((today - Duration::days(1), 1)),
+            [n, "day" | "days", "ago", ..] => n
+                .parse::<u32>()
+                .ok()
+                .filter(|n| *n <= MAX_DAYS_AGO)
+                .and_then(|n| today.checked_sub_days(Days::new(n.into())))
+                .map(|date| (date, 3)),
+            ["last", day, ..] => weekday(day).map(|day| (previous(today, day), 2)),
+            [day, ..] if weekday(day).is_some() => {
+                let day = weekday(day)?;
+                let date = if today.weekday() == day { today } else { previous(today, day) };
+                Some((date, 1))
+            }
+            [first, second, ..] if month_day(first, second, today).is_some() => {
+                month_day(first, second, today).map(|date| (date, 2))
+            }
+            [word, ..] => numeric_date(word, today).map(|date| (date, 1)),
+            [] => None,
+        };
+        if let Some((date, len)) = found {
+            tokens[i..i + len].iter_mut().for_each(|t| t.used = true);
+            return Some(date);
+        }
+    }
+    None
+}
+
+/// Full weekday names only, so words like "sun" or "wed" stay in the note.
+fn weekday(word: &str) -> Option<Weekday> {
+    word.parse::<Weekday>().ok().filter(|_| word.len() > 3)
+}
+
+/// The most recent `day` before `today`.
+fn previous(today: NaiveDate, day: Weekday) -> NaiveDate {
+    let back = (today.weekday().num_days_from_monday() + 7 - day.num_days_from_monday() - 1) % 7 + 1;
+    today - Duration::days(back as i64)
+}
+
+/// "12 feb" or "feb 12th", in the most recent year that isn't in the future.
+fn month_day(first: &str, second: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let strip = |w: &str| {
+        w.trim_end_matches(|c: char| c.is_ascii_alphabetic())
+            .parse::<u32>()
+            .ok()
+            .filter(|_| !w.starts_with(|c: char| c.is_ascii_alphabetic()))
+    };
+    let (day, month) = match (strip(first), strip(second)) {
+        (Some(day), None) => (day, second),
+        (None, Some(day)) => (day, first),
+        _ => return None,
+    };
+    let date = |year: i32| NaiveDate::parse_from_str(&format!("{} {} {}", day, month, year), "%d %b %Y").ok();
+    date(today.year()).filter(|d| *d <= today).or_else(|| date(today.year() - 1))
+}
+
+/// "2026-02-12", "12/02/2026", "12/02/26" or "12/02". Day first.
+fn numeric_date(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    for format in ["%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y", "%d/%m/%y", "%d-%m-%y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(word, format) {
+            if date.year() >= 2000 {
+                return Some(date);
+            }
+        }
+    }
+    // Without a year: this year, or last year if that would be in the future
+    let (day, month) = word.split_once('/')?;
+    let date = |year: i32| NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?);
+    date(today.year()).filter(|d| *d <= today).or_else(|| date(today.year() - 1))
+}
+
+/// The amount, preferring one marked with a currency ("₹120", "120rs",
+/// "inr 120"). Otherwise the largest number is taken, as in "2 samosas 40".
+fn take_amount(tokens: &mut [Token]) -> Option<(Decimal, Confidence)> {
+    let candidates: Vec<(usize, Decimal, bool)> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| !t.used)
+        .filter_map(|(i, t)| {
+            let (amount, symbol) = parse_amount(&t.word)?;
+            let is_currency = |j: usize| tokens.get(j).is_some_and(|t| CURRENCY_WORDS.contains(&t.word.as_str()));
+            let marked = symbol || is_currency(i + 1) || (i > 0 && is_currency(i - 1));
+            Some((i, amount, marked))
+        })
+        .filter(|(_, amount, _)| *amount > Decimal::ZERO)
+        .collect();
+
+    let marked = candidates.iter().find(|(_, _, marked)| *marked);
+    let (index, amount, confidence) = match marked {
+        Some((i, amount, _)) => (*i, *amount, Confidence::High),
+        None => {
+            let (i, amount, _) = candidates.iter().max_by_key(|(_, amount, _)| *amount)?;
+            let confidence = if candidates.len() == 1 { Confidence::High } else { Confidence::Medium };
+            (*i, *amount, confidence)
+        }
+    };
+
+    tokens[index].used = true;
+    tokens
+        .iter_mut()
+        .filter(|t| CURRENCY_WORDS.contains(&t.word.as_str()))
+        .for_each(|t| t.used = true);
+    Some((amount, confidence))
+}
+
+/// A number such as "120", "1,250.50", "₹120", "120rs", "450/-" or "15k",
+/// and whether it carried a currency symbol.
+fn parse_amount(word: &str) -> Option<(Decimal, bool)> {
+    let mut symbol = false;
+    let mut number = word;
+    for prefix in ["₹", "rs.", "rs", "inr"] {
+        if let Some(rest) = number.strip_prefix(prefix) {
+            number = rest;
+            symbol = true;
+            break;
+        }
+    }
+    for suffix in ["/-", "rs", "inr"] {
+        if let Some(rest) = number.strip_suffix(suffix) {
+            number = rest;
+            symbol = true;
+            break;
+        }
+    }
+    let (number, multiplier) = match number.strip_suffix('k') {
+        Some(rest) => (rest, Decimal::ONE_THOUSAND),
+        None => (number, Decimal::ONE),
+    };
+
+    if !number.starts_with(|c: char| c.is_ascii_digit())
+        || !number.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+    {
+        return None;
+    }
+    let amount = Decimal::from_str(&number.replace(',', "")).ok()?.checked_mul(multiplier)?;
+    Some((amount.round_dp(2), symbol))
+}
+
+/// One of the user's categories named in the text, otherwise one suggested
+/// by keyword, otherwise "other". Only a named category is removed from the
+/// note; keywords such as "coffee" or "uber" describe the expense.
+fn take_category(tokens: &mut [Token], categories: &HashSet<String>) -> (String, Confidence) {
+    let words: Vec<String> = tokens
+        .iter()
+        .map(|t| t.word.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .collect();
+
+    let mut named: Vec<(&String, usize, usize)> = categories
+        .iter()
+        .filter_map(|category| {
+            let name: Vec<&str> = category.split_whitespace().collect();
+            if name.is_empty() {
+                return None;
+            }
+            (0..(words.len() + 1).saturating_sub(name.len()))
+                .find(|&i| {
+                    (0..name.len()).all(|j| !tokens[i + j].used && words[i + j] == name[j])
+                })
+                .map(|i| (category, i, name.len()))
+        })
+        .collect();
+    // Prefer the longest name, e.g. "eating out" over "out"
+    named.sort_by_key(|(category, _, _)| std::cmp::Reverse(category.len()));
+    if let Some((category, i, len)) = named.first() {
+        tokens[*i..*i + *len].iter_mut().for_each(|t| t.used = true);
+        return ((*category).clone(), Confidence::High);
+    }
+
+    let rest: Vec<&str> = tokens.iter().filter(|t| !t.used).map(|t| t.text).collect();
+    match categorizer::suggest_category(&rest.join(" "), categories) {
+        Some(category) => (category, Confidence::Medium),
+        None => ("other".to_string(), Confidence::Low),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 2, 18).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    fn categories() -> HashSet<String> {
+        ["food", "travel", "bills", "shopping", "eating out", "other"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    fn quick_add(text: &str) -> (CreateExpense, QuickAddConfidence) {
+        parse(text, &categories(), today()).unwrap()
+    }
+
+    #[test]
+    fn amount_keyword_and_relative_date() {
+        let (expense, confidence) = quick_add("120 coffee yesterday");
+        assert_eq!(expense.amount, Decimal::from(120));
+        assert_eq!(expense.category, "food");
+        assert_eq!(expense.note.as_deref(), Some("coffee"));
+        assert_eq!(expense.expense_date, date(2026, 2, 17));
+        assert_eq!(
+            confidence,
+            QuickAddConfidence {
+                amount: Confidence::High,
+                category: Confidence::Medium,
+                date: Confidence::High,
+            }
+        );
+    }
+
+    #[test]
+    fn named_category_is_not_part_of_the_note() {
+        let (expense, confidence) = quick_add("uber 350 travel");
+        assert_eq!(expense.amount, Decimal::from(350));
+        assert_eq!(expense.category, "travel");
+        assert_eq!(expense.note.as_deref(), Some("uber"));
+        assert_eq!(expense.expense_date, Some(today()));
+        assert_eq!(confidence.category, Confidence::High);
+        assert_eq!(confidence.date, Confidence::Medium);
+    }
+
+    #[test]
+    fn longest_category_name_wins() {
+        let (expense, _) = quick_add("Dinner eating out 900");
+        assert_eq!(expense.category, "eating out");
+        assert_eq!(expense.note.as_deref(), Some("Dinner"));
+    }
+
+    #[test]
+    fn currency_marks_the_amount() {
+        let (expense, confidence) = quick_add("2 movie tickets 500rs last friday");
+        assert_eq!(expense.amount, Decimal::from(500));
+        assert_eq!(confidence.amount, Confidence::High);
+        assert_eq!(expense.note.as_deref(), Some("2 movie tickets"));
+        assert_eq!(expense.expense_date, date(2026, 2, 13));
+
+        let (expense, _) = quick_add("spent ₹1,250.50 on Amazon shoes");
+        assert_eq!(expense.amount, Decimal::new(125050, 2));
+        assert_eq!(expense.category, "shopping");
+        assert_eq!(expense.note.as_deref(), Some("Amazon shoes"));
+
+        let (expense, _) = quick_add("paid rs 40 for chai");
+        assert_eq!(expense.amount, Decimal::from(40));
+        assert_eq!(expense.note.as_deref(), Some("chai"));
+    }
+
+    #[test]
+    fn unmarked_amounts_take_the_largest() {
+        let (expense, confidence) = quick_add("3 samosas 60");
+        assert_eq!(expense.amount, Decimal::from(60));
+        assert_eq!(confidence.amount, Confidence::Medium);
+        assert_eq!(expense.note.as_deref(), Some("3 samosas"));
+    }
+
+    #[test]
+    fn hashtags_become_tags() {
+        let (expense, _) = quick_add("taxi 640 #Work-Trip #reimbursable");
+        assert_eq!(expense.tags, vec!["work-trip", "reimbursable"]);
+        assert_eq!(expense.note.as_deref(), Some("taxi"));
+    }
+
+    #[test]
+    fn thousands_shorthand() {
+        let (expense, _) = quick_add("rent 15k");
+        assert_eq!(expense.amount, Decimal::from(15000));
+        assert_eq!(expense.category, "bills");
+    }
+
+    #[test]
+    fn oversized_shorthand_is_not_an_amount() {
+        let text = "79228162514264337593543950335k tea";
+        assert!(parse(text, &categories(), today()).is_err());
+        let (expense, _) = quick_add(&format!("50 {}", text));
+        assert_eq!(expense.amount, Decimal::from(50));
+    }
+
+    #[test]
+    fn dates() {
+        let cases = [
+            ("50 tea today", date(2026, 2, 18)),
+            ("50 tea day before yesterday", date(2026, 2, 16)),
+            ("50 tea 3 days ago", date(2026, 2, 15)),
+            ("50 tea monday", date(2026, 2, 16)),
+            ("50 tea wednesday", date(2026, 2, 18)),
+            ("50 tea last wednesday", date(2026, 2, 11)),
+            ("50 tea on 12 feb", date(2026, 2, 12)),
+            ("50 tea on Dec 24th", date(2025, 12, 24)),
+            ("50 tea 2026-01-31", date(2026, 1, 31)),
+            ("50 tea 05/02/2026", date(2026, 2, 5)),
+            ("50 tea 05/02", date(2026, 2, 5)),
+            ("50 tea 05/03", date(2025, 3, 5)),
+        ];
+        for (text, expected) in cases {
+            let (expense, _) = quick_add(text);
+            assert_eq!(expense.expense_date, expected, "{}", text);
+            assert_eq!(expense.amount, Decimal::from(50), "{}", text);
+            assert_eq!(expense.note.as_deref(), Some("tea"), "{}", text);
+        }
+    }
+
+    #[test]
+    fn day_counts_out_of_range_are_not_dates() {
+        for text in ["50 tea 99999999 days ago", "50 tea -3 days ago", "50 tea 3651 days ago"] {
+            let (expense, confidence) = quick_add(text);
+            assert_eq!(expense.expense_date, Some(today()), "{}", text);
+            assert_eq!(confidence.date, Confidence::Medium, "{}", text);
+        }
+        let (expense, _) = quick_add("50 tea 3650 days ago");
+        assert_eq!(expense.expense_date, date(2016, 2, 21));
+    }
+
+    #[test]
+    fn unknown_category_falls_back_to_other() {
+        let (expense, confidence) = quick_add("450 haircut");
+        assert_eq!(expense.category, "other");
+        assert_eq!(confidence.category, Confidence::Low);
+    }
+
+    #[test]
+    fn amount_is_required() {
+        assert!(parse("coffee yesterday", &categories(), today()).is_err());
+        assert!(parse("coffee 0", &categories(), today()).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+
+/// What could be read off a receipt's OCR text. Any part may be missing.
+#[derive(Debug, Default, PartialEq)]
+pub struct ParsedReceipt {
+    pub merchant: Option<String>,
+    pub total: Option<Decimal>,
+    pub date: Option<NaiveDate>,
+}
+
+/// Labels of the amount paid, most specific first.
+const TOTAL_LABELS: &[&str] = &[
+    "grand total", "net total", "total amount", "amount due", "net amount", "net payable",
+    "amount payable", "balance due", "total due", "bill amount", "total", "amount",
+];
+
+/// Lines that mention a total but aren't the amount paid.
+const NOT_TOTAL: &[&str] = &[
+    "subtotal", "sub total", "total qty", "total quantity", "total items", "total tax",
+    "total gst", "total discount", "total savings", "item total",
+];
+
+/// Words on header lines that aren't the merchant's name.
+const NOT_MERCHANT: &[&str] = &[
+    "welcome", "tax", "invoice", "receipt", "bill", "gstin", "gst", "tel", "ph", "phone",
+    "date", "time", "www", "http", "order", "cash memo",
+];
+
+/// How many lines from the top to look at for the merchant.
+const MERCHANT_LINES: usize = 5;
+
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y", "%d.%m.%Y", "%d/%m/%y", "%d-%m-%y", "%d.%m.%y",
+    "%d-%b-%Y", "%d-%b-%y", "%d/%b/%Y", "%d %b %Y", "%d %B %Y", "%b %d %Y", "%B %d %Y",
+    // Month first only when the day-first reading is impossible
+    "%m/%d/%Y", "%m/%d/%y",
+];
+
+/// Pull the merchant, total and date out of OCR text. Dates are read day
+/// first, and only dates between 2000 and `today` are accepted.
+pub fn parse(text: &str, today: NaiveDate) -> ParsedReceipt {
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    ParsedReceipt {
+        merchant: find_merchant(&lines),
+        total: find_total(&lines),
+        date: find_date(&lines, today),
+    }
+}
+
+fn find_merchant(lines: &[&str]) -> Option<String> {
+    lines.iter().take(MERCHANT_LINES).find_map(|line| {
+        let letters = line.chars().filter(|c| c.is_alphabetic()).count();
+        let visible = line.chars().filter(|c| !c.is_whitespace()).count();
+        if letters < 3 || letters * 2 < visible {
+            return None;
+        }
+        let words = format!(" {} ", words(line).join(" "));
+        if NOT_MERCHANT.iter().any(|w| words.contains(&format!(" {} ", w))) {
+            return None;
+        }
+        let name = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        Some(name.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+    })
+}
+
+/// The amount next to the most specific total label, taking the last such
+/// line since totals come at the bottom. The amount may be on the line below
+/// the label. Without a label, the largest amount with paise is used.
+fn find_total(lines: &[&str]) -> Option<Decimal> {
+    for label in TOTAL_LABELS {
+        let found = lines.iter().enumerate().rev().find_map(|(i, line)| {
+            let lower = line.to_lowercase();
+            if !lower.contains(label) || NOT_TOTAL.iter().any(|n| lower.contains(n)) {
+                return None;
+            }
+            amounts(line)
+                .last()
+                .copied()
+                .or_else(|| lines.get(i + 1).and_then(|next| amounts(next).last().copied()))
+                .map(|(amount, _)| amount)
+        });
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    lines
+        .iter()
+        .flat_map(|line| amounts(line))
+        .filter(|(_, has_paise)| *has_paise)
+        .map(|(amount, _)| amount)
+        .max()
+}
+
+/// Amounts on a line, each with whether it had a decimal part.
+fn amounts(line: &str) -> Vec<(Decimal, bool)> {
+    line.split(|c: char| c.is_whitespace() || c == ':')
+        .filter_map(|token| {
+            let lower = token.to_lowercase();
+            let token = ["₹", "rs.", "rs", "inr", "$", "€", "£"]
+                .iter()
+                .find_map(|prefix| lower.strip_prefix(prefix))
+                .unwrap_or(&lower)
+                .trim_end_matches("/-");
+            parse_amount(token)
+        })
+        .collect()
+}
+
+fn parse_amount(token: &str) -> Option<(Decimal, bool)> {
+    if !token.chars().any(|c| c.is_ascii_digit())
+        || !token.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+    {
+        return None;
+    }
+    // OCR often reads the decimal point as a comma: "450,00"
+    let cleaned = match token.rsplit_once(',') {
+        Some((_, paise)) if !token.contains('.') && token.matches(',').count() == 1 && paise.len() == 2 => {
+            token.replace(',', ".")
+        }
+        _ => token.replace(',', ""),
+    };
+    let has_paise = match cleaned.rsplit_once('.') {
+        Some((_, paise)) if paise.len() == 2 => true,
+        Some(_) => return None,
+        None => false,
+    };
+    Decimal::from_str(&cleaned).ok().map(|d| (d, has_paise))
+}
+
+fn find_date(lines: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    lines.iter().find_map(|line| {
+        let tokens: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|t| !t.is_empty())
+            .flat_map(|t| t.rsplit_once(':').map(|(_, v)| v).or(Some(t)))
+            .collect();
+        // Single tokens like 14/02/2026, then three-token dates like 12 Jan 2026
+        let candidates = tokens
+            .iter()
+            .map(|t| t.to_string())
+            .chain(tokens.windows(3).map(|w| w.join(" ")));
+        candidates.into_iter().find_map(|candidate| {
+            // A future date is a misread, not a cue to try month first
+            DATE_FORMATS
+                .iter()
+                .find_map(|format| {
+                    NaiveDate::parse_from_str(&candidate, format)
+                        .ok()
+                        .filter(|d| d.year() >= 2000)
+                })
+                .filter(|d| *d <= today)
+        })
+    })
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 2, 20).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[test]
+    fn grocery_receipt() {
+        let receipt = parse(include_str!("../../tests/fixtures/receipts/grocery.txt"), today());
+        assert_eq!(receipt.merchant.as_deref(), Some("DMART"));
+        // Not the subtotal, the item count or the cash tendered
+        assert_eq!(receipt.total, Some(Decimal::new(54180, 2)));
+        assert_eq!(receipt.date, date(2026, 2, 14));
+    }
+
+    #[test]
+    fn restaurant_receipt() {
+        let receipt = parse(include_str!("../../tests/fixtures/receipts/restaurant.txt"), today());
+        assert_eq!(receipt.merchant.as_deref(), Some("The Filter Coffee House"));
+        assert_eq!(receipt.total, Some(Decimal::new(27300, 2)));
+        assert_eq!(receipt.date, date(2026, 2, 3));
+    }
+
+    #[test]
+    fn fuel_receipt() {
+        let receipt = parse(include_str!("../../tests/fixtures/receipts/fuel.txt"), today());
+        assert_eq!(receipt.merchant.as_deref(), Some("HPCL - SRI BALAJI FUELS"));
+        assert_eq!(receipt.total, Some(Decimal::new(200000, 2)));
+        assert_eq!(receipt.date, date(2026, 1, 12));
+    }
+
+    #[test]
+    fn total_on_the_next_line() {
+        let receipt = parse("Corner Store\nTOTAL\nRs. 1,250.50\n", today());
+        assert_eq!(receipt.total, Some(Decimal::new(125050, 2)));
+    }
+
+    #[test]
+    fn unlabelled_total_uses_largest_amount_with_paise() {
+        let receipt = parse("Corner Store\nPh 9845012345\nBread 45.00\nMilk 28,00\n", today());
+        assert_eq!(receipt.total, Some(Decimal::new(4500, 2)));
+    }
+
+    #[test]
+    fn future_and_ancient_dates_are_ignored() {
+        let receipt = parse("Shop\n01/03/2026\n05/01/1999\n18/02/26\n", today());
+        assert_eq!(receipt.date, date(2026, 2, 18));
+    }
+
+    #[test]
+    fn unreadable_text() {
+        assert_eq!(parse("~~ ## ~~\n", today()), ParsedReceipt::default());
+    }
+}
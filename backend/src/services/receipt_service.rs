@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use crate::domain::expense::{CreateExpense, ReceiptScan};
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::category_repo;
+use crate::services::categorizer;
+use crate::services::ocr::OcrEngine;
+use crate::services::receipt_parser;
+
+/// Read a receipt photo and draft an expense from it. Nothing is saved.
+pub async fn scan_receipt(
+    pool: &PgPool,
+    ocr: Arc<dyn OcrEngine>,
+    user: &User,
+    image: Vec<u8>,
+) -> Result<ReceiptScan, AppError> {
+    if image.is_empty() {
+        return Err(AppError::Validation("Uploaded image is empty".into()));
+    }
+    let categories: HashSet<String> = category_repo::find_all(pool, user.id)
+        .await?
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    scan_image(ocr, image, &categories, user.today()).await
+}
+
+async fn scan_image(
+    ocr: Arc<dyn OcrEngine>,
+    image: Vec<u8>,
+    categories: &HashSet<String>,
+    today: NaiveDate,
+) -> Result<ReceiptScan, AppError> {
+    let text = tokio::task::spawn_blocking(move || ocr.recognize(&image))
+        .await
+        .map_err(|e| AppError::Internal(format!("OCR task failed: {}", e)))??;
+    let receipt = receipt_parser::parse(&text, today);
+
+    // The merchant's name is the best hint; item lines help for unknown shops
+    let category = receipt
+        .merchant
+        .as_deref()
+        .and_then(|m| categorizer::suggest_category(m, categories))
+        .or_else(|| categorizer::suggest_category(&text, categories))
+        .unwrap_or_else(|| "other".to_string());
+    let draft = receipt.total.map(|amount| CreateExpense {
+        amount,
+        currency: None,
+        category,
+        note: receipt.merchant.clone(),
+        tags: Vec::new(),
+        expense_date: receipt.date,
+    });
+
+    Ok(ReceiptScan {
+        merchant: receipt.merchant,
+        total: receipt.total,
+        date: receipt.date,
+        draft,
+        text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    /// Returns canned text, standing in for a real OCR engine.
+    struct FixedOcr(&'static str);
+
+    impl OcrEngine for FixedOcr {
+        fn recognize(&self, _image: &[u8]) -> Result<String, AppError> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    fn categories() -> HashSet<String> {
+        ["food", "groceries", "fuel", "other"].iter().map(|c| c.to_string()).collect()
+    }
+
+    async fn scan(text: &'static str) -> ReceiptScan {
+        let today = NaiveDate::from_ymd_opt(2026, 2, 20).unwrap();
+        scan_image(Arc::new(FixedOcr(text)), vec![0], &categories(), today).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn drafts_an_expense_from_each_sample() {
+        let samples = [
+            (include_str!("../../tests/fixtures/receipts/grocery.txt"), "groceries", 54180),
+            (include_str!("../../tests/fixtures/receipts/restaurant.txt"), "food", 27300),
+            (include_str!("../../tests/fixtures/receipts/fuel.txt"), "fuel", 200000),
+        ];
+        for (text, category, paise) in samples {
+            let draft = scan(text).await.draft.expect("a draft expense");
+            assert_eq!(draft.category, category);
+            assert_eq!(draft.amount, Decimal::new(paise, 2));
+            assert!(draft.expense_date.is_some());
+            assert!(draft.note.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn no_draft_without_a_total() {
+        let result = scan("Corner Store\nThank you\n").await;
+        assert_eq!(result.merchant.as_deref(), Some("Corner Store"));
+        assert!(result.draft.is_none());
+    }
+}
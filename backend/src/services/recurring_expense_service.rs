@@ -0,0 +1,165 @@
+use chrono::Duration;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::domain::expense::CreateExpense;
+use crate::domain::recurring_expense::*;
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::{expense_repo, recurring_expense_repo, user_repo};
+use crate::services::{category_service, currency_service, notification_service};
+
+pub async fn create_recurring_expense(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    input: &CreateRecurringExpense,
+) -> Result<RecurringExpense, AppError> {
+    if input.amount < Decimal::ZERO {
+        return Err(AppError::Validation("Amount cannot be negative".into()));
+    }
+    let mut input = input.clone();
+    input.category = category_service::ensure_exists(pool, user.id, &input.category).await?;
+
+    let start_date = input.start_date.unwrap_or_else(|| user.today());
+    if input.end_date.is_some_and(|end| end < start_date) {
+        return Err(AppError::Validation("end_date cannot be before start_date".into()));
+    }
+
+    let currency = currency_service::resolve(user, input.currency.as_deref())?;
+    currency_service::ensure_convertible(pool, &currency, &user.base_currency, start_date).await?;
+
+    let recurring =
+        recurring_expense_repo::create(pool, user.id, &input, &currency, start_date).await?;
+
+    // Materialize right away if the first run is already due
+    if recurring.next_run_date <= user.today() {
+        materialize(pool, config, recurring.id).await?;
+        return get_recurring_expense(pool, recurring.id, user.id).await;
+    }
+    Ok(recurring)
+}
+
+pub async fn get_recurring_expense(
+    pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<RecurringExpense, AppError> {
+    recurring_expense_repo::find_by_id(pool, id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Recurring expense not found".to_string()))
+}
+
+pub async fn list_recurring_expenses(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<RecurringExpense>, AppError> {
+    recurring_expense_repo::find_all(pool, user_id).await
+}
+
+pub async fn update_recurring_expense(
+    pool: &PgPool,
+    id: Uuid,
+    user: &User,
+    input: &UpdateRecurringExpense,
+) -> Result<RecurringExpense, AppError> {
+    if input.amount.is_some_and(|a| a < Decimal::ZERO) {
+        return Err(AppError::Validation("Amount cannot be negative".into()));
+    }
+    let mut input = input.clone();
+    if let Some(category) = &input.category {
+        input.category = Some(category_service::ensure_exists(pool, user.id, category).await?);
+    }
+    let existing = get_recurring_expense(pool, id, user.id).await?;
+    if input.end_date.flatten().is_some_and(|end| end < existing.start_date) {
+        return Err(AppError::Validation("end_date cannot be before start_date".into()));
+    }
+
+    // Resuming skips the runs missed while paused rather than backfilling them
+    let resume_from = if input.active == Some(true) {
+        let frequency: Frequency = existing.frequency.parse().map_err(AppError::Internal)?;
+        let yesterday = user.today() - Duration::days(1);
+        Some(frequency.next_occurrence(existing.start_date, yesterday))
+    } else {
+        None
+    };
+
+    recurring_expense_repo::update(pool, id, user.id, &input, resume_from)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Recurring expense not found".to_string()))
+}
+
+pub async fn delete_recurring_expense(
+    pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<(), AppError> {
+    if !recurring_expense_repo::delete(pool, id, user_id).await? {
+        return Err(AppError::NotFound("Recurring expense not found".to_string()));
+    }
+    Ok(())
+}
+
+/// Create the expenses for every due rule. Safe to run concurrently and
+/// repeatedly: see [`materialize`].
+pub async fn materialize_due(pool: &PgPool, config: &Config) -> Result<(), AppError> {
+    for id in recurring_expense_repo::find_due_ids(pool).await? {
+        if let Err(e) = materialize(pool, config, id).await {
+            tracing::error!("Failed to materialize recurring expense {}: {}", id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Create an expense for each occurrence of the rule up to today in the
+/// owner's timezone, then move `next_run_date` past them. The row lock and the
+/// date advance commit in the same transaction as the expenses, so an
+/// occurrence is only ever materialized once.
+async fn materialize(pool: &PgPool, config: &Config, id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    let Some(recurring) = recurring_expense_repo::lock_for_run(&mut *tx, id).await? else {
+        return Ok(());
+    };
+    if !recurring.active {
+        return Ok(());
+    }
+    let frequency: Frequency = recurring.frequency.parse().map_err(AppError::Internal)?;
+    let user = user_repo::find_by_id(pool, recurring.user_id)
+        .await?
+        .ok_or_else(|| AppError::Internal("Recurring expense owner not found".into()))?;
+    let today = user.today();
+
+    let mut next = recurring.next_run_date;
+    let mut last_run = None;
+    let in_range = |date| !matches!(recurring.end_date, Some(end) if date > end);
+    while next <= today && in_range(next) {
+        let expense = CreateExpense {
+            amount: recurring.amount,
+            currency: Some(recurring.currency.clone()),
+            category: recurring.category.clone(),
+            note: recurring.note.clone(),
+            tags: Vec::new(),
+            expense_date: Some(next),
+        };
+        expense_repo::create(&mut *tx, recurring.user_id, &expense, &recurring.currency, next).await?;
+        last_run = Some(next);
+        next = frequency.next_occurrence(recurring.start_date, next);
+    }
+
+    recurring_expense_repo::advance(&mut *tx, id, next, last_run, in_range(next)).await?;
+    tx.commit().await?;
+
+    if let Some(date) = last_run {
+        tracing::info!("Materialized recurring expense {} through {}", id, date);
+        notification_service::spawn_category_budget_checks(
+            pool,
+            config,
+            &user,
+            vec![(recurring.category, date)],
+        );
+    }
+    Ok(())
+}
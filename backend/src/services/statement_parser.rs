@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::domain::expense::StatementFormat;
+
+/// A single transaction read from a bank statement. Debits have a negative
+/// `amount`, credits a positive one. `currency` is only known for OFX.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementTransaction {
+    pub fitid: String,
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub payee: Option<String>,
+    pub memo: Option<String>,
+}
+
+impl StatementTransaction {
+    pub fn is_debit(&self) -> bool {
+        self.amount < Decimal::ZERO
+    }
+
+    /// Payee and memo combined into a single expense note.
+    pub fn note(&self) -> Option<String> {
+        match (&self.payee, &self.memo) {
+            (Some(payee), Some(memo)) if !payee.eq_ignore_ascii_case(memo) => {
+                Some(format!("{} - {}", payee, memo))
+            }
+            (Some(payee), _) => Some(payee.clone()),
+            (None, memo) => memo.clone(),
+        }
+    }
+}
+
+/// Guess the statement format from its contents.
+pub fn detect_format(data: &str) -> Option<StatementFormat> {
+    let head = data.trim_start().to_ascii_uppercase();
+    if head.starts_with("OFXHEADER") || head.starts_with("<?XML") || head.contains("<OFX>") {
+        Some(StatementFormat::Ofx)
+    } else if head.starts_with("!TYPE:") || head.starts_with("!ACCOUNT") || head.starts_with("!OPTION") {
+        Some(StatementFormat::Qif)
+    } else {
+        None
+    }
+}
+
+pub fn parse(data: &str, format: StatementFormat, day_first: bool) -> Result<Vec<StatementTransaction>, String> {
+    match format {
+        StatementFormat::Ofx => parse_ofx(data),
+        StatementFormat::Qif => parse_qif(data, day_first),
+    }
+}
+
+/// Parse the `<STMTTRN>` entries of an OFX (or QFX) statement. Both the SGML
+/// flavour of OFX 1.x, where leaf elements have no closing tags, and the XML
+/// flavour of OFX 2.x are accepted.
+pub fn parse_ofx(data: &str) -> Result<Vec<StatementTransaction>, String> {
+    let upper = data.to_ascii_uppercase();
+    if !upper.contains("<OFX>") {
+        return Err("Not an OFX statement".to_string());
+    }
+
+    let currency = ofx_field(data, &upper, "CURDEF");
+    let mut fitids = FitidGenerator::default();
+    let mut transactions = Vec::new();
+    let mut rest = 0;
+    while let Some(start) = upper[rest..].find("<STMTTRN>") {
+        let start = rest + start + "<STMTTRN>".len();
+        let end = ["</STMTTRN>", "<STMTTRN>", "</BANKTRANLIST>"]
+            .iter()
+            .filter_map(|tag| upper[start..].find(tag))
+            .min()
+            .map(|i| start + i)
+            .unwrap_or(data.len());
+        let block = &data[start..end];
+        let block_upper = &upper[start..end];
+        rest = end;
+
+        let n = transactions.len() + 1;
+        let field = |tag: &str| ofx_field(block, block_upper, tag);
+        let date = field("DTPOSTED")
+            .ok_or_else(|| format!("Transaction {}: missing DTPOSTED", n))
+            .and_then(|d| parse_ofx_date(&d).ok_or_else(|| format!("Transaction {}: invalid date '{}'", n, d)))?;
+        let amount = field("TRNAMT")
+            .ok_or_else(|| format!("Transaction {}: missing TRNAMT", n))
+            .and_then(|a| parse_signed_amount(&a).ok_or_else(|| format!("Transaction {}: invalid amount '{}'", n, a)))?;
+        let payee = field("NAME").or_else(|| field("PAYEE"));
+        let memo = field("MEMO");
+        let fitid = match field("FITID") {
+            Some(fitid) => fitid,
+            None => fitids.synthesize("ofx", date, amount, payee.as_deref()),
+        };
+
+        transactions.push(StatementTransaction {
+            fitid,
+            date,
+            amount,
+            currency: currency.clone(),
+            payee,
+            memo,
+        });
+    }
+    Ok(transactions)
+}
+
+/// The value of a leaf element, which ends at the next tag or line break.
+fn ofx_field(block: &str, block_upper: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = block_upper.find(&open)? + open.len();
+    let value = &block[start..];
+    let end = value.find(['<', '\r', '\n']).unwrap_or(value.len());
+    let value = decode_entities(value[..end].trim());
+    (!value.is_empty()).then_some(value)
+}
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// OFX dates look like `20260105`, `20260105120000` or
+/// `20260105120000.000[+5.30:IST]`; only the calendar date is used.
+fn parse_ofx_date(value: &str) -> Option<NaiveDate> {
+    let digits = value.get(..8)?;
+    NaiveDate::parse_from_str(digits, "%Y%m%d").ok()
+}
+
+/// Parse the transactions of a QIF bank, cash or credit card account. QIF
+/// has no transaction ids, so a stable id is derived from each transaction's
+/// date, amount, payee and position among identical transactions.
+pub fn parse_qif(data: &str, day_first: bool) -> Result<Vec<StatementTransaction>, String> {
+    let mut fitids = FitidGenerator::default();
+    let mut transactions = Vec::new();
+    let mut in_transactions = false;
+    let mut record = QifRecord::default();
+
+    for (i, line) in data.lines().enumerate() {
+        let line = line.trim_end();
+        let line_no = i + 1;
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('!') {
+            let header = header.to_lowercase();
+            if let Some(kind) = header.strip_prefix("type:") {
+                in_transactions = matches!(kind.trim(), "bank" | "cash" | "ccard" | "oth a" | "oth l");
+                if !in_transactions && !matches!(kind.trim(), "cat" | "class" | "memorized") {
+                    return Err(format!("Line {}: unsupported QIF account type '{}'", line_no, kind.trim()));
+                }
+            } else if header.starts_with("account") {
+                in_transactions = false;
+            }
+            record = QifRecord::default();
+            continue;
+        }
+
+        if !in_transactions {
+            continue;
+        }
+
+        let (code, value) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+        let value = value.trim();
+        match code {
+            "D" => {
+                record.date = Some(
+                    parse_qif_date(value, day_first)
+                        .ok_or_else(|| format!("Line {}: invalid date '{}'", line_no, value))?,
+                )
+            }
+            "T" | "U" => {
+                record.amount = Some(
+                    parse_signed_amount(value)
+                        .ok_or_else(|| format!("Line {}: invalid amount '{}'", line_no, value))?,
+                )
+            }
+            "P" => record.payee = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "M" => record.memo = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "^" => {
+                let record = std::mem::take(&mut record);
+                let date = record
+                    .date
+                    .ok_or_else(|| format!("Line {}: transaction has no date", line_no))?;
+                let amount = record
+                    .amount
+                    .ok_or_else(|| format!("Line {}: transaction has no amount", line_no))?;
+                let fitid = fitids.synthesize("qif", date, amount, record.payee.as_deref());
+                transactions.push(StatementTransaction {
+                    fitid,
+                    date,
+                    amount,
+                    currency: None,
+                    payee: record.payee,
+                    memo: record.memo,
+                });
+            }
+            // Cleared status, check number, category, address and split lines
+            _ => {}
+        }
+    }
+    Ok(transactions)
+}
+
+#[derive(Default)]
+struct QifRecord {
+    date: Option<NaiveDate>,
+    amount: Option<Decimal>,
+    payee: Option<String>,
+    memo: Option<String>,
+}
+
+/// QIF dates come in many shapes: `1/5/2026`, `01/05/26`, `1/ 5'26` or
+/// `2026-01-05`. Month comes first unless `day_first` is set.
+fn parse_qif_date(value: &str, day_first: bool) -> Option<NaiveDate> {
+    let parts: Vec<u32> = value
+        .split(['/', '-', '.', '\''])
+        .map(|p| p.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let [a, b, c] = parts[..] else {
+        return None;
+    };
+    let (year, month, day) = if value.trim().split(['/', '-', '.']).next()?.len() == 4 {
+        (a, b, c)
+    } else {
+        let year = if c < 100 { 2000 + c } else { c };
+        if day_first {
+            (year, b, a)
+        } else {
+            (year, a, b)
+        }
+    };
+    NaiveDate::from_ymd_opt(year as i32, month, day)
+}
+
+/// Parse a signed amount, ignoring thousands separators and currency symbols.
+/// A lone comma is taken as the decimal separator.
+fn parse_signed_amount(value: &str) -> Option<Decimal> {
+    let mut cleaned: String = value
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '₹' | '$' | '€' | '£'))
+        .collect();
+    if cleaned.matches(',').count() == 1 && !cleaned.contains('.') && cleaned.rsplit(',').next()?.len() != 3 {
+        cleaned = cleaned.replace(',', ".");
+    } else {
+        cleaned = cleaned.replace(',', "");
+    }
+    Decimal::from_str(&cleaned).ok()
+}
+
+/// Derives ids for transactions the bank did not give one. Identical
+/// transactions in the same statement are told apart by a sequence number.
+#[derive(Default)]
+struct FitidGenerator {
+    seen: HashMap<String, usize>,
+}
+
+impl FitidGenerator {
+    fn synthesize(&mut self, prefix: &str, date: NaiveDate, amount: Decimal, payee: Option<&str>) -> String {
+        let key = format!(
+            "{}:{}:{}:{}",
+            prefix,
+            date.format("%Y%m%d"),
+            amount.normalize(),
+            payee.unwrap_or("").trim().to_lowercase()
+        );
+        let n = self.seen.entry(key.clone()).or_insert(0);
+        *n += 1;
+        format!("{}:{}", key, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parses_sgml_ofx() {
+        let data = include_str!("../../tests/fixtures/statement_sgml.ofx");
+        assert_eq!(detect_format(data), Some(StatementFormat::Ofx));
+
+        let txns = parse_ofx(data).unwrap();
+        assert_eq!(txns.len(), 3);
+        assert_eq!(txns[0].fitid, "202601050001");
+        assert_eq!(txns[0].date, date(2026, 1, 5));
+        assert_eq!(txns[0].amount, Decimal::from_str("-450.00").unwrap());
+        assert_eq!(txns[0].currency.as_deref(), Some("INR"));
+        assert_eq!(txns[0].payee.as_deref(), Some("SWIGGY BANGALORE"));
+        assert_eq!(txns[0].note().as_deref(), Some("SWIGGY BANGALORE - UPI/Food order"));
+        assert!(!txns[1].is_debit());
+        assert_eq!(txns[2].payee.as_deref(), Some("AMAZON & CO"));
+        assert_eq!(txns[2].amount, Decimal::from_str("-1299.50").unwrap());
+    }
+
+    #[test]
+    fn parses_xml_ofx() {
+        let data = include_str!("../../tests/fixtures/statement_xml.ofx");
+        assert_eq!(detect_format(data), Some(StatementFormat::Ofx));
+
+        let txns = parse_ofx(data).unwrap();
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0].fitid, "TXN-9001");
+        assert_eq!(txns[0].date, date(2026, 2, 1));
+        assert_eq!(txns[1].memo.as_deref(), Some("Monthly plan"));
+        assert!(txns.iter().all(StatementTransaction::is_debit));
+    }
+
+    #[test]
+    fn parses_qif() {
+        let data = include_str!("../../tests/fixtures/statement.qif");
+        assert_eq!(detect_format(data), Some(StatementFormat::Qif));
+
+        let txns = parse_qif(data, false).unwrap();
+        assert_eq!(txns.len(), 4);
+        assert_eq!(txns[0].date, date(2026, 1, 5));
+        assert_eq!(txns[0].amount, Decimal::from_str("-1250.00").unwrap());
+        assert_eq!(txns[1].date, date(2026, 1, 7));
+        assert!(!txns[2].is_debit());
+
+        // Identical transactions get distinct, stable ids
+        assert_ne!(txns[0].fitid, txns[3].fitid);
+        assert_eq!(parse_qif(data, false).unwrap()[3].fitid, txns[3].fitid);
+    }
+
+    #[test]
+    fn qif_day_first_dates() {
+        assert_eq!(parse_qif_date("05/01/2026", true), Some(date(2026, 1, 5)));
+        assert_eq!(parse_qif_date("05/01/2026", false), Some(date(2026, 5, 1)));
+        assert_eq!(parse_qif_date("1/ 7'26", false), Some(date(2026, 1, 7)));
+        assert_eq!(parse_qif_date("2026-01-05", false), Some(date(2026, 1, 5)));
+        assert_eq!(parse_qif_date("13/13/2026", false), None);
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        assert_eq!(detect_format("date,amount\n2026-01-01,10"), None);
+        assert!(parse_ofx("not a statement").is_err());
+        assert!(parse_qif("!Type:Invst\nD1/1/2026\n^", false).is_err());
+    }
+
+    #[test]
+    fn amounts() {
+        assert_eq!(parse_signed_amount("-1,250.00"), Decimal::from_str("-1250.00").ok());
+        assert_eq!(parse_signed_amount("-250,50"), Decimal::from_str("-250.50").ok());
+        assert_eq!(parse_signed_amount("₹ 99"), Decimal::from_str("99").ok());
+        assert_eq!(parse_signed_amount("abc"), None);
+    }
+}
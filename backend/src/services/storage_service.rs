@@ -0,0 +1,128 @@
+use aws_sdk_s3::primitives::ByteStream;
+
+use crate::errors::AppError;
+
+/// How long presigned download URLs stay valid.
+const PRESIGN_EXPIRY_SECS: u64 = 3600;
+
+/// Object storage for uploaded files. Talks to AWS S3 by default, or to any
+/// S3-compatible server (such as MinIO) when an endpoint URL is given.
+#[derive(Clone)]
+pub struct StorageService {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+}
+
+impl StorageService {
+    pub async fn new(bucket: String, endpoint_url: Option<&str>) -> Self {
+        let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let client = match endpoint_url {
+            Some(url) => {
+                // S3-compatible servers generally don't do virtual-hosted buckets
+                let s3_config = aws_sdk_s3::config::Builder::from(&config)
+                    .endpoint_url(url)
+                    .force_path_style(true)
+                    .build();
+                aws_sdk_s3::Client::from_conf(s3_config)
+            }
+            None => aws_sdk_s3::Client::new(&config),
+        };
+        Self { client, bucket }
+    }
+
+    pub fn client(&self) -> &aws_sdk_s3::Client {
+        &self.client
+    }
+
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    pub async fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), AppError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(data))
+            .content_type(content_type)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("S3 upload failed: {}", e)))?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), AppError> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("S3 delete failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// Delete objects that no longer have a row pointing at them. Failures are
+    /// logged rather than returned, since the rows are already gone.
+    pub async fn delete_all(&self, keys: &[String]) {
+        for key in keys {
+            if let Err(e) = self.delete(key).await {
+                tracing::error!("Failed to delete {} from storage: {}", key, e);
+            }
+        }
+    }
+
+    pub async fn presign_url(&self, key: &str) -> Result<String, AppError> {
+        use aws_sdk_s3::presigning::PresigningConfig;
+        use std::time::Duration;
+
+        let presigned = self.client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .presigned(PresigningConfig::expires_in(Duration::from_secs(PRESIGN_EXPIRY_SECS))
+                .map_err(|e| AppError::Internal(format!("Presign config error: {}", e)))?)
+            .await
+            .map_err(|e| AppError::Internal(format!("Presign error: {}", e)))?;
+
+        Ok(presigned.uri().to_string())
+    }
+}
+
+/// These run against a local S3-compatible server rather than AWS:
+///
+/// ```sh
+/// docker compose -f deployment/docker-compose.yml up -d minio
+/// AWS_ACCESS_KEY_ID=minioadmin AWS_SECRET_ACCESS_KEY=minioadmin AWS_REGION=us-east-1 \
+///     S3_ENDPOINT_URL=http://localhost:9000 cargo test -- --ignored
+/// ```
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_BUCKET: &str = "lily-test";
+
+    async fn storage() -> StorageService {
+        let endpoint = std::env::var("S3_ENDPOINT_URL").expect("S3_ENDPOINT_URL must be set");
+        let storage = StorageService::new(TEST_BUCKET.to_string(), Some(&endpoint)).await;
+        // Already existing is fine
+        let _ = storage.client().create_bucket().bucket(TEST_BUCKET).send().await;
+        storage
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a local S3-compatible server"]
+    async fn put_presign_and_delete() {
+        let storage = storage().await;
+        let key = format!("receipts/test/{}.txt", uuid::Uuid::new_v4());
+
+        storage.put(&key, b"receipt".to_vec(), "text/plain").await.unwrap();
+        let url = storage.presign_url(&key).await.unwrap();
+        let body = reqwest::get(&url).await.unwrap().error_for_status().unwrap().bytes().await.unwrap();
+        assert_eq!(&body[..], b"receipt");
+
+        storage.delete(&key).await.unwrap();
+        let status = reqwest::get(&url).await.unwrap().status();
+        assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+    }
+}
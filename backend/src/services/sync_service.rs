@@ -0,0 +1,406 @@
+use chrono::{DateTime, NaiveDate, SubsecRound, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::domain::expense::{normalize_tags, CreateExpense, Expense};
+use crate::domain::meeting_note::MeetingNote;
+use crate::domain::pagination::{decode_cursor, encode_cursor};
+use crate::domain::sync::*;
+use crate::domain::user::User;
+use crate::errors::AppError;
+use crate::infrastructure::{expense_repo, meeting_note_repo, sync_repo};
+use crate::services::{category_service, currency_service, notification_service};
+
+const MAX_TITLE_LEN: usize = 255;
+
+/// A validated mutation, ready to write.
+enum Write<T> {
+    Upsert(T),
+    Delete,
+}
+
+struct ExpenseWrite {
+    input: CreateExpense,
+    currency: String,
+    date: NaiveDate,
+}
+
+struct NoteWrite {
+    title: String,
+    duration_secs: i32,
+}
+
+/// Apply the client's offline mutations, then return what changed on the
+/// server since its last sync. Each mutation wins only if it was made after
+/// the server's copy was last edited or deleted.
+pub async fn sync(
+    pool: &PgPool,
+    config: &Config,
+    user: &User,
+    request: &SyncRequest,
+) -> Result<SyncResponse, AppError> {
+    if request.expenses.len() + request.meeting_notes.len() > MAX_MUTATIONS {
+        return Err(AppError::Validation(format!(
+            "At most {} mutations can be synced at once",
+            MAX_MUTATIONS
+        )));
+    }
+    let token: SyncToken = match &request.sync_token {
+        Some(token) => decode_cursor(token)
+            .map_err(|_| AppError::Validation("Invalid sync token".into()))?,
+        None => SyncToken::default(),
+    };
+
+    // Validate everything first so the writes share one short transaction
+    let mut expenses = Vec::with_capacity(request.expenses.len());
+    for mutation in &request.expenses {
+        expenses.push(validate_expense(pool, user, mutation).await?);
+    }
+    let notes: Vec<_> = request.meeting_notes.iter().map(validate_note).collect();
+
+    let mut results = Vec::with_capacity(expenses.len() + notes.len());
+    // Category and day of each expense logged offline, to check budgets and
+    // mark days as submitted
+    let mut written = Vec::new();
+    let mut tx = pool.begin().await?;
+    for (mutation, write) in request.expenses.iter().zip(expenses) {
+        let outcome = match write {
+            Ok(write) => apply_expense(&mut tx, user.id, mutation, write, &mut written).await?,
+            Err(error) => Err(error),
+        };
+        results.push(result(SyncEntity::Expense, mutation.id, outcome));
+    }
+    for (mutation, write) in request.meeting_notes.iter().zip(notes) {
+        let outcome = match write {
+            Ok(write) => apply_note(&mut tx, user.id, mutation, write).await?,
+            Err(error) => Err(error),
+        };
+        results.push(result(SyncEntity::MeetingNote, mutation.id, outcome));
+    }
+    tx.commit().await?;
+
+    let mut dates: Vec<_> = written.iter().map(|(_, date)| *date).collect();
+    dates.sort();
+    dates.dedup();
+    for date in dates {
+        let _ = expense_repo::mark_daily_submission(pool, user.id, date).await;
+    }
+    notification_service::spawn_category_budget_checks(pool, config, user, written);
+
+    let (changes, sync_token, has_more) = pull(pool, user.id, token).await?;
+    Ok(SyncResponse {
+        results,
+        changes,
+        sync_token: encode_cursor(&sync_token),
+        has_more,
+    })
+}
+
+/// `Ok` if a mutation was applied, otherwise the status and reason it wasn't.
+type Outcome<T> = Result<T, (MutationStatus, Option<String>)>;
+
+fn rejected<T>(error: impl Into<String>) -> Outcome<T> {
+    Err((MutationStatus::Rejected, Some(error.into())))
+}
+
+fn conflict<T>() -> Outcome<T> {
+    Err((MutationStatus::Conflict, Some("Changed on the server since".into())))
+}
+
+fn result(entity: SyncEntity, id: Uuid, outcome: Outcome<()>) -> MutationResult {
+    let (status, error) = match outcome {
+        Ok(()) => (MutationStatus::Applied, None),
+        Err((status, error)) => (status, error),
+    };
+    MutationResult { entity, id, status, error }
+}
+
+async fn validate_expense(
+    pool: &PgPool,
+    user: &User,
+    mutation: &ExpenseMutation,
+) -> Result<Outcome<Write<ExpenseWrite>>, AppError> {
+    if mutation.deleted {
+        return Ok(Ok(Write::Delete));
+    }
+    let (Some(amount), Some(category)) = (mutation.amount, &mutation.category) else {
+        return Ok(rejected("amount and category are required"));
+    };
+    let checked = async {
+        let category = category_service::ensure_exists(pool, user.id, category).await?;
+        let tags = normalize_tags(&mutation.tags).map_err(AppError::Validation)?;
+        let date = mutation.expense_date.unwrap_or_else(|| user.today());
+        let currency = currency_service::resolve(user, mutation.currency.as_deref())?;
+        currency_service::ensure_convertible(pool, &currency, &user.base_currency, date).await?;
+        let input = CreateExpense {
+            amount,
+            currency: Some(currency.clone()),
+            category,
+            note: mutation.note.clone(),
+            tags,
+            expense_date: Some(date),
+        };
+        Ok(ExpenseWrite { input, currency, date })
+    };
+    match checked.await {
+        Ok(write) => Ok(Ok(Write::Upsert(write))),
+        Err(AppError::Validation(error)) => Ok(rejected(error)),
+        Err(e) => Err(e),
+    }
+}
+
+fn validate_note(mutation: &MeetingNoteMutation) -> Outcome<Write<NoteWrite>> {
+    if mutation.deleted {
+        return Ok(Write::Delete);
+    }
+    let title = mutation
+        .meeting_title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or("Untitled Meeting");
+    if title.chars().count() > MAX_TITLE_LEN {
+        return rejected(format!("meeting_title can be at most {} characters", MAX_TITLE_LEN));
+    }
+    let duration_secs = mutation.duration_secs.unwrap_or(0);
+    if duration_secs < 0 {
+        return rejected("duration_secs cannot be negative");
+    }
+    Ok(Write::Upsert(NoteWrite { title: title.to_string(), duration_secs }))
+}
+
+/// The edit time used to resolve conflicts: what the database can store,
+/// and never in the future, so a fast client clock can't win every conflict.
+fn edit_time(updated_at: DateTime<Utc>) -> DateTime<Utc> {
+    updated_at.trunc_subsecs(6).min(Utc::now())
+}
+
+/// Compare a mutation with the server's last edit of the row. `None` means
+/// the mutation is newer and should be written.
+fn resolve(server: Option<DateTime<Utc>>, client: DateTime<Utc>) -> Option<Outcome<()>> {
+    match server {
+        Some(server) if server > client => Some(conflict()),
+        // The same edit, retried after a lost response
+        Some(server) if server == client => Some(Ok(())),
+        _ => None,
+    }
+}
+
+async fn apply_expense(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    mutation: &ExpenseMutation,
+    write: Write<ExpenseWrite>,
+    written: &mut Vec<(String, NaiveDate)>,
+) -> Result<Outcome<()>, AppError> {
+    let updated_at = edit_time(mutation.updated_at);
+    let existing = expense_repo::find_for_sync(&mut **tx, mutation.id).await?;
+    if existing.is_some_and(|e| e.user_id != user_id) {
+        return Ok(rejected("Unknown expense id"));
+    }
+    // A trashed expense counts as deleted, as does a purged one
+    let live = existing.filter(|e| e.deleted_at.is_none());
+    if let Some(outcome) = resolve(live.map(|e| e.updated_at), updated_at) {
+        return Ok(outcome);
+    }
+
+    match write {
+        Write::Delete => {
+            if live.is_some() {
+                expense_repo::delete(&mut **tx, mutation.id, user_id).await?;
+            }
+            Ok(Ok(()))
+        }
+        Write::Upsert(write) => {
+            if live.is_none() {
+                let deleted_at =
+                    sync_repo::deleted_at(&mut **tx, SyncEntity::Expense, mutation.id, user_id).await?;
+                if let Some(outcome) = resolve(deleted_at, updated_at) {
+                    return Ok(outcome);
+                }
+                if deleted_at.is_some() {
+                    sync_repo::remove_tombstone(&mut **tx, SyncEntity::Expense, mutation.id).await?;
+                }
+            }
+            let saved = expense_repo::upsert(
+                &mut **tx,
+                mutation.id,
+                user_id,
+                &write.input,
+                &write.currency,
+                write.date,
+                updated_at,
+            )
+            .await?;
+            // Nothing saved means a concurrent sync got there first
+            if saved.is_none() {
+                return Ok(conflict());
+            }
+            written.push((write.input.category, write.date));
+            Ok(Ok(()))
+        }
+    }
+}
+
+async fn apply_note(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    mutation: &MeetingNoteMutation,
+    write: Write<NoteWrite>,
+) -> Result<Outcome<()>, AppError> {
+    let updated_at = edit_time(mutation.updated_at);
+    let existing = meeting_note_repo::find_for_sync(&mut **tx, mutation.id).await?;
+    if existing.is_some_and(|n| n.user_id != user_id) {
+        return Ok(rejected("Unknown meeting note id"));
+    }
+    let live = existing.filter(|n| n.deleted_at.is_none());
+    if let Some(outcome) = resolve(live.map(|n| n.updated_at), updated_at) {
+        return Ok(outcome);
+    }
+
+    match write {
+        Write::Delete => {
+            if live.is_some() {
+                meeting_note_repo::delete(&mut **tx, mutation.id, user_id).await?;
+            }
+            Ok(Ok(()))
+        }
+        Write::Upsert(write) => {
+            if live.is_none() {
+                let deleted_at =
+                    sync_repo::deleted_at(&mut **tx, SyncEntity::MeetingNote, mutation.id, user_id).await?;
+                if let Some(outcome) = resolve(deleted_at, updated_at) {
+                    return Ok(outcome);
+                }
+                if deleted_at.is_some() {
+                    sync_repo::remove_tombstone(&mut **tx, SyncEntity::MeetingNote, mutation.id).await?;
+                }
+            }
+            let saved = meeting_note_repo::upsert(
+                &mut **tx,
+                mutation.id,
+                user_id,
+                &write.title,
+                write.duration_secs,
+                updated_at,
+            )
+            .await?;
+            Ok(if saved.is_some() { Ok(()) } else { conflict() })
+        }
+    }
+}
+
+enum Change {
+    Expense(Expense),
+    MeetingNote(MeetingNote),
+    Deleted(Tombstone),
+}
+
+/// Rows written since `token`, oldest version first, and the token for the
+/// next pull.
+async fn pull(
+    pool: &PgPool,
+    user_id: Uuid,
+    token: SyncToken,
+) -> Result<(SyncChanges, SyncToken, bool), AppError> {
+    // Taken before reading, so anything not yet visible is at or above it
+    let next = if token.after == 0 { sync_repo::snapshot_xmin(pool).await? } else { token.next };
+    let limit = MAX_CHANGES as i64 + 1;
+
+    let mut rows: Vec<(i64, Change)> = Vec::new();
+    rows.extend(
+        sync_repo::changed_expenses(pool, user_id, &token, limit)
+            .await?
+            .into_iter()
+            .map(|v| (v.row_version, Change::Expense(v.row))),
+    );
+    rows.extend(
+        sync_repo::changed_meeting_notes(pool, user_id, &token, limit)
+            .await?
+            .into_iter()
+            .map(|v| (v.row_version, Change::MeetingNote(v.row))),
+    );
+    rows.extend(
+        sync_repo::tombstones(pool, user_id, &token, limit)
+            .await?
+            .into_iter()
+            .map(|v| (v.row_version, Change::Deleted(v.row))),
+    );
+    Ok(collect(rows, token, next))
+}
+
+/// Keep the oldest `MAX_CHANGES` rows. While more remain, the next pull
+/// carries on after the last version sent; once caught up, it starts over
+/// from the transaction floor taken when this round of pulls began.
+fn collect(
+    mut rows: Vec<(i64, Change)>,
+    token: SyncToken,
+    next: i64,
+) -> (SyncChanges, SyncToken, bool) {
+    rows.sort_by_key(|(version, _)| *version);
+    let has_more = rows.len() > MAX_CHANGES;
+    rows.truncate(MAX_CHANGES);
+
+    let next_token = match rows.last() {
+        Some((version, _)) if has_more => SyncToken { since: token.since, after: *version, next },
+        _ => SyncToken { since: next, after: 0, next: 0 },
+    };
+    let mut changes = SyncChanges::default();
+    for (_, change) in rows {
+        match change {
+            Change::Expense(e) => changes.expenses.push(e),
+            Change::MeetingNote(n) => changes.meeting_notes.push(n),
+            Change::Deleted(t) => changes.deleted.push(t),
+        }
+    }
+    (changes, next_token, has_more)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_771_000_000 + secs, 0).unwrap()
+    }
+
+    fn tombstone(version: i64) -> (i64, Change) {
+        let t = Tombstone { entity: "expense".into(), entity_id: Uuid::new_v4(), deleted_at: at(0) };
+        (version, Change::Deleted(t))
+    }
+
+    #[test]
+    fn later_edit_wins() {
+        assert!(resolve(None, at(0)).is_none());
+        assert!(resolve(Some(at(0)), at(1)).is_none());
+        assert!(matches!(resolve(Some(at(1)), at(0)), Some(Err((MutationStatus::Conflict, _)))));
+        // A retry of an edit that was already saved
+        assert!(matches!(resolve(Some(at(1)), at(1)), Some(Ok(()))));
+    }
+
+    #[test]
+    fn future_edit_times_are_capped() {
+        let future = Utc::now() + chrono::Duration::days(1);
+        assert!(edit_time(future) <= Utc::now());
+    }
+
+    #[test]
+    fn caught_up_pull_restarts_from_floor() {
+        let token = SyncToken { since: 100, after: 0, next: 0 };
+        let (changes, next, more) = collect(vec![tombstone(7), tombstone(3)], token, 250);
+        assert!(!more);
+        assert_eq!(changes.deleted.len(), 2);
+        assert_eq!(next, SyncToken { since: 250, after: 0, next: 0 });
+    }
+
+    #[test]
+    fn paged_pull_continues_after_last_version() {
+        let rows: Vec<_> = (1..=MAX_CHANGES as i64 + 1).rev().map(tombstone).collect();
+        let token = SyncToken { since: 100, after: 0, next: 0 };
+        let (changes, next, more) = collect(rows, token, 250);
+        assert!(more);
+        assert_eq!(changes.deleted.len(), MAX_CHANGES);
+        assert_eq!(next, SyncToken { since: 100, after: MAX_CHANGES as i64, next: 250 });
+    }
+}
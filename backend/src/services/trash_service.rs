@@ -0,0 +1,56 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::expense::Expense;
+use crate::domain::meeting_note::MeetingNote;
+use crate::domain::trash::Trash;
+use crate::errors::AppError;
+use crate::infrastructure::{expense_repo, meeting_note_repo};
+use crate::services::storage_service::StorageService;
+
+pub async fn list_trash(pool: &PgPool, user_id: Uuid, retention_days: i32) -> Result<Trash, AppError> {
+    Ok(Trash {
+        expenses: expense_repo::find_deleted(pool, user_id).await?,
+        meeting_notes: meeting_note_repo::find_deleted(pool, user_id).await?,
+        retention_days,
+    })
+}
+
+pub async fn restore_expense(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<Expense, AppError> {
+    expense_repo::restore(pool, id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Expense not found in trash".to_string()))
+}
+
+pub async fn restore_meeting_note(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<MeetingNote, AppError> {
+    meeting_note_repo::restore(pool, id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Meeting note not found in trash".to_string()))
+}
+
+/// Permanently delete everything trashed more than `retention_days` ago,
+/// then its files. Files are left in place when their storage isn't
+/// configured.
+pub async fn purge_expired(
+    pool: &PgPool,
+    retention_days: i32,
+    attachments: Option<&StorageService>,
+    meeting_storage: Option<&StorageService>,
+) -> Result<(), AppError> {
+    let attachment_keys = expense_repo::purge_deleted(pool, retention_days).await?;
+    remove_files(attachments, &attachment_keys, "attachment").await;
+
+    let meeting_keys = meeting_note_repo::purge_deleted(pool, retention_days).await?;
+    remove_files(meeting_storage, &meeting_keys, "meeting audio and photo").await;
+    Ok(())
+}
+
+async fn remove_files(storage: Option<&StorageService>, keys: &[String], kind: &str) {
+    if keys.is_empty() {
+        return;
+    }
+    match storage {
+        Some(storage) => storage.delete_all(keys).await,
+        None => tracing::warn!("Storage not configured; left {} purged {} files in place", keys.len(), kind),
+    }
+}
@@ -1,11 +1,11 @@
 import client from './client';
-import type { Expense, CreateExpenseInput, UpdateExpenseInput, ExpenseFilter, DailyStatus } from '../types';
+import type { Expense, CreateExpenseInput, UpdateExpenseInput, ExpenseFilter, DailyStatus, Page } from '../types';
 
 export const createExpense = (data: CreateExpenseInput) =>
   client.post<Expense>('/expenses', data);
 
 export const getExpenses = (filter?: ExpenseFilter) =>
-  client.get<Expense[]>('/expenses', { params: filter });
+  client.get<Page<Expense>>('/expenses', { params: filter });
 
 export const getExpense = (id: string) =>
   client.get<Expense>(`/expenses/${id}`);
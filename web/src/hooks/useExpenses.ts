@@ -5,34 +5,66 @@ import type { CreateExpenseInput } from '../types';
 
 export function useExpenses(filter?: ExpenseFilter) {
   const [expenses, setExpenses] = useState<Expense[]>([]);
+  const [nextCursor, setNextCursor] = useState<string | null>(null);
+  const [total, setTotal] = useState(0);
   const [loading, setLoading] = useState(true);
+  const [loadingMore, setLoadingMore] = useState(false);
 
   const fetchExpenses = useCallback(async () => {
     setLoading(true);
     try {
       const { data } = await getExpenses(filter);
-      setExpenses(data);
+      setExpenses(data.items);
+      setNextCursor(data.next_cursor);
+      setTotal(data.total);
     } catch (err) {
       console.error('Failed to fetch expenses:', err);
     } finally {
       setLoading(false);
     }
-  }, [filter?.start_date, filter?.end_date, filter?.category, filter?.page]);
+  }, [filter?.start_date, filter?.end_date, filter?.category, filter?.per_page]);
 
   useEffect(() => {
     fetchExpenses();
   }, [fetchExpenses]);
 
+  const loadMore = async () => {
+    if (!nextCursor || loadingMore) return;
+    setLoadingMore(true);
+    try {
+      const { data } = await getExpenses({ ...filter, cursor: nextCursor });
+      setExpenses((prev) => [...prev, ...data.items]);
+      setNextCursor(data.next_cursor);
+      setTotal(data.total);
+    } catch (err) {
+      console.error('Failed to fetch more expenses:', err);
+    } finally {
+      setLoadingMore(false);
+    }
+  };
+
   const addExpense = async (input: CreateExpenseInput) => {
     const { data } = await createExpense(input);
     setExpenses((prev) => [data, ...prev]);
+    setTotal((prev) => prev + 1);
     return data;
   };
 
   const removeExpense = async (id: string) => {
     await deleteExpense(id);
     setExpenses((prev) => prev.filter((e) => e.id !== id));
+    setTotal((prev) => prev - 1);
   };
 
-  return { expenses, loading, refetch: fetchExpenses, addExpense, removeExpense };
+  return {
+    expenses,
+    total,
+    hasMore: nextCursor !== null,
+    loading,
+    loadingMore,
+    loadMore,
+    refetch: fetchExpenses,
+    addExpense,
+    removeExpense,
+  };
 }
@@ -17,7 +17,7 @@ export default function HistoryPage() {
     per_page: 50,
   };
 
-  const { expenses, loading, removeExpense } = useExpenses(filter);
+  const { expenses, total, hasMore, loading, loadingMore, loadMore, removeExpense } = useExpenses(filter);
 
   const inputStyle = {
     padding: '8px 12px', borderRadius: 6, border: '1px solid #d1d5db', fontSize: 14,
@@ -48,7 +48,26 @@ export default function HistoryPage() {
       {loading ? (
         <p style={{ textAlign: 'center', color: '#6b7280' }}>Loading...</p>
       ) : (
-        <ExpenseList expenses={expenses} onDelete={removeExpense} />
+        <>
+          <p style={{ color: '#6b7280', fontSize: 14, marginBottom: 12 }}>
+            Showing {expenses.length} of {total} expenses
+          </p>
+          <ExpenseList expenses={expenses} onDelete={removeExpense} />
+          {hasMore && (
+            <div style={{ textAlign: 'center', marginTop: 16 }}>
+              <button
+                onClick={loadMore}
+                disabled={loadingMore}
+                style={{
+                  padding: '8px 16px', borderRadius: 6, border: '1px solid #d1d5db',
+                  background: 'white', fontSize: 14, cursor: 'pointer',
+                }}
+              >
+                {loadingMore ? 'Loading...' : 'Load more'}
+              </button>
+            </div>
+          )}
+        </>
       )}
     </div>
   );
@@ -3,6 +3,9 @@ export interface User {
   email: string;
   name: string;
   notification_time: string;
+  timezone: string;
+  base_currency: string;
+  anomaly_alerts: boolean;
   created_at: string;
 }
 
@@ -10,8 +13,10 @@ export interface Expense {
   id: string;
   user_id: string;
   amount: string;
+  currency: string;
   category: Category;
   note: string | null;
+  tags: string[];
   expense_date: string;
   created_at: string;
   updated_at: string;
@@ -23,15 +28,19 @@ export const CATEGORIES: Category[] = ['food', 'entertainment', 'travel', 'bills
 
 export interface CreateExpenseInput {
   amount: number;
+  currency?: string;
   category: Category;
   note?: string;
+  tags?: string[];
   expense_date?: string;
 }
 
 export interface UpdateExpenseInput {
   amount?: number;
+  currency?: string;
   category?: Category;
   note?: string;
+  tags?: string[];
   expense_date?: string;
 }
 
@@ -39,10 +48,16 @@ export interface ExpenseFilter {
   start_date?: string;
   end_date?: string;
   category?: Category;
-  page?: number;
+  cursor?: string;
   per_page?: number;
 }
 
+export interface Page<T> {
+  items: T[];
+  next_cursor: string | null;
+  total: number;
+}
+
 export interface CategorySummary {
   category: string;
   total: string;